@@ -0,0 +1,74 @@
+//! Times building, walking and saving a [`GeneOntology`] about the size of a
+//! human GO annotation set. Run with `cargo run --release --example arena_bench`.
+
+use rolemodel_gsea::{ChainRng, GeneOntology, GeneSet, NameIndex, Node, Part, Saveable, SaveableData, Whole};
+use rand::{Rng, SeedableRng};
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+const NGENES: usize = 13_809;
+const NTERMS: usize = 15_000;
+const RUNS: usize = 3;
+
+/// Best of `RUNS` timings of `f`.
+fn best_of<T>(mut f: impl FnMut() -> T) -> (Duration, T) {
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            let out = f();
+            (start.elapsed(), out)
+        })
+        .min_by_key(|(elapsed, _)| *elapsed)
+        .unwrap()
+}
+
+fn main() {
+    // Term sizes are heavy tailed, as in GO.
+    let mut rng = ChainRng::seed_from_u64(1);
+    let mut adj = Vec::new();
+    for term_idx in 0..NTERMS {
+        let size = (5.0 / rng.gen::<f64>().max(0.002)).min(2000.0) as usize;
+        let mut seen = HashSet::new();
+        for _ in 0..size {
+            let gene_idx = rng.gen_range(0..NGENES);
+            if seen.insert(gene_idx) {
+                adj.push((term_idx, gene_idx));
+            }
+        }
+    }
+    println!("{} genes, {} terms, {} annotations", NGENES, NTERMS, adj.len());
+
+    let build = || GeneOntology::from_named_incidence(
+        NameIndex::numbered(NGENES),
+        (0..NGENES).map(|idx| SaveableData::new(idx % 10 == 0, false)).collect(),
+        NameIndex::numbered(NTERMS),
+        vec![SaveableData::new(false, false); NTERMS],
+        adj.clone(),
+    );
+    let (elapsed, mut gene_ontology) = best_of(build);
+    println!("build: {:?}", elapsed);
+
+    let (elapsed, total) = best_of(|| {
+        let mut total = 0usize;
+        for _ in 0..20 {
+            for term in gene_ontology.iter_wholes() {
+                total += term.parts().filter(|gene| gene.data().current()).count();
+            }
+            for gene in gene_ontology.iter_parts() {
+                total += gene.wholes().filter(|term| !term.data().current()).count();
+            }
+        }
+        total
+    });
+    println!("20 full traversals: {:?} ({} neighbours)", elapsed, total);
+
+    let (elapsed, _) = best_of(|| {
+        for _ in 0..200 {
+            gene_ontology.save();
+            gene_ontology.restore();
+        }
+    });
+    println!("200 save/restore: {:?}", elapsed);
+}
@@ -1,25 +1,38 @@
 pub mod genes;
 pub mod terms;
+pub mod loader;
+pub mod id_map;
+pub mod names;
+pub mod binary;
+pub mod graph;
+pub mod namespace;
+pub mod filter;
+pub mod collapse;
+pub mod components;
+pub mod overlap;
+pub mod subgraph;
+pub mod edit;
+mod arena;
 
 use crate::{
     gene_ontology::{
-        genes::Gene,
-        terms::Term,
+        arena::Arena,
+        genes::{Gene, GeneMut},
+        names::NameIndex,
+        namespace::Namespace,
+        terms::{Term, TermMut},
     }, rolemodel::{
+        checkpoint::{ChainRng, Checkpoint},
         genelist::GeneListRolemodel,
-        saveable::Saveable,
-    }, Activeable, GeneSet, Node, Part, Whole
+        saveable::{Saveable, SaveableData},
+    }, Activeable, GeneSet, GeneSetMut, Node, NodeMut
 };
 use rv::data::Booleable;
 
 use std::{
-    cell::RefCell,
-    rc::Rc,
-    ops::Deref,
+    collections::BTreeMap,
+    marker::PhantomData,
 };
-use hashbrown::{HashMap, HashSet};
-
-use itertools::Itertools;
 
 // impl<Td, Gd> RmPart for Rc<RefCell<genes::Gene<Td, Gd>>> 
 // where 
@@ -43,427 +56,181 @@ use itertools::Itertools;
 //     }
 // }
 
+/// Bipartite graph of genes and the terms annotating them.
+///
+/// Every gene and term keeps its name, and optionally a description, in a
+/// [`NameIndex`] aligned with its index. Genes and terms are handed out as
+/// [`Gene`] and [`Term`] handles borrowing index-based storage, and their data
+/// is changed through [`GeneSetMut`].
+/// Terms may also know their GO [`Namespace`].
 #[derive(Debug)]
 pub struct GeneOntology<Td, Gd> {
-    genes: Vec<Rc<RefCell<genes::Gene<Td, Gd>>>>,
-    terms: Vec<Rc<RefCell<terms::Term<Td, Gd>>>>,
+    arena: Arena<Td, Gd>,
+    gene_names: NameIndex,
+    term_names: NameIndex,
+    term_namespaces: Vec<Option<Namespace>>,
 }
-impl<Td, Gd> GeneOntology<Td, Gd> 
-where
-    Td: std::fmt::Debug,
-    Gd: std::fmt::Debug,
-{
-    pub fn new(
-        genes: Vec<Rc<RefCell<genes::Gene<Td, Gd>>>>,
-        terms: Vec<Rc<RefCell<terms::Term<Td, Gd>>>>,
-    ) -> Self {
-        Self {
-            genes,
-            terms
-        }
+impl<Td, Gd> GeneOntology<Td, Gd> {
+    /// Unannotated genes and terms, named by their index.
+    pub fn new(gene_data: Vec<Gd>, term_data: Vec<Td>) -> Self {
+        Self::from_incidence(gene_data, term_data, vec![])
     }
 
-    pub fn genes(&self) -> &Vec<Rc<RefCell<genes::Gene<Td, Gd>>>> {
-        &self.genes
-    }
-    pub fn terms(&self) -> &Vec<Rc<RefCell<terms::Term<Td, Gd>>>> {
-        &self.terms
+    pub fn genes(&self) -> impl ExactSizeIterator<Item = Gene<'_, Td, Gd>> {
+        (0..self.arena.gene_data.len()).map(|idx| Gene::new(&self.arena, idx))
     }
-    pub fn mut_terms(&mut self) -> &mut Vec<Rc<RefCell<terms::Term<Td, Gd>>>> {
-        &mut self.terms
+    pub fn terms(&self) -> impl ExactSizeIterator<Item = Term<'_, Td, Gd>> {
+        (0..self.arena.term_data.len()).map(|idx| Term::new(&self.arena, idx))
     }
 
-    pub fn from_incidence(gene_data: Vec<Gd>, term_data: Vec<Td>, adj: Vec<(usize, usize)>) -> Self {
-        let gene_ontology = Self::new(
-            gene_data.into_iter().map(|d| Rc::new(RefCell::new(Gene::new(d, vec![])))).collect(),
-            term_data.into_iter().map(|d| Rc::new(RefCell::new(Term::new(d, vec![])))).collect(),
-        );
-
-
-        adj.iter()
-            .for_each(|(term_idx, gene_idx)| {
-                let new_term = gene_ontology.terms[*term_idx].clone();
-                gene_ontology.genes[*gene_idx].borrow_mut().add_term(new_term);
-                
-
-                let new_gene = gene_ontology.genes[*gene_idx].clone();
-                gene_ontology.terms[*term_idx].borrow_mut().add_gene(new_gene);
-            });
-
+    pub fn gene_names(&self) -> &NameIndex {
+        &self.gene_names
+    }
+    pub fn term_names(&self) -> &NameIndex {
+        &self.term_names
+    }
+    pub fn gene_names_mut(&mut self) -> &mut NameIndex {
+        &mut self.gene_names
+    }
+    pub fn term_names_mut(&mut self) -> &mut NameIndex {
+        &mut self.term_names
+    }
 
-        gene_ontology
+    pub fn term_namespace(&self, idx: usize) -> Option<Namespace> {
+        *self.term_namespaces.get(idx)?
+    }
+    pub fn set_term_namespace(&mut self, idx: usize, namespace: Namespace) {
+        self.term_namespaces[idx] = Some(namespace);
     }
 
-}
+    pub fn gene_index(&self, name: &str) -> Option<usize> {
+        self.gene_names.get(name)
+    }
+    pub fn term_index(&self, name: &str) -> Option<usize> {
+        self.term_names.get(name)
+    }
+    pub fn gene(&self, name: &str) -> Option<Gene<'_, Td, Gd>> {
+        self.part(self.gene_index(name)?)
+    }
+    pub fn term(&self, name: &str) -> Option<Term<'_, Td, Gd>> {
+        self.whole(self.term_index(name)?)
+    }
 
+    /// Genes and terms are named by their index.
+    pub fn from_incidence(gene_data: Vec<Gd>, term_data: Vec<Td>, adj: Vec<(usize, usize)>) -> Self {
+        let gene_names = NameIndex::numbered(gene_data.len());
+        let term_names = NameIndex::numbered(term_data.len());
+        Self::from_named_incidence(gene_names, gene_data, term_names, term_data, adj)
+    }
 
-impl<Td, Gd> GeneOntology<Td, Gd>
-where 
-    for<'a> Td: Default + std::fmt::Debug,
-    for<'a> Gd: Default + std::fmt::Debug,
-{
-    pub fn read_scalar_data(
-        gene_data_file: Option<String>, 
-        gene_name_header: Option<String>, gene_data_header: Option<String>,
-        term_data_file: Option<String>, 
-        term_name_header: Option<String>, term_data_header: Option<String>,
-        adj_file: String, 
-        adj_gene_header: Option<String>, adj_term_header: Option<String>,
-    ) -> Self 
-    where 
-        for<'a> Gd: serde::de::Deserialize<'a>,
-        for<'a> Td: serde::de::Deserialize<'a>,
-    {
-        Self::read_apply_scalar_data(
-            gene_data_file, gene_name_header, gene_data_header,
-            term_data_file, term_name_header, term_data_header,
-            adj_file, adj_gene_header, adj_term_header,
-            |x: Gd| x, |x: Td| x,
-        )
-    }
-    pub fn read_apply_scalar_data<Dg, Fg, Dt, Ft>(
-        gene_data_file: Option<String>, 
-        gene_name_header: Option<String>, gene_data_header: Option<String>,
-        term_data_file: Option<String>, 
-        term_name_header: Option<String>, term_data_header: Option<String>,
-        adj_file: String, 
-        adj_gene_header: Option<String>, adj_term_header: Option<String>,
-        f_gene: Fg, f_term: Ft,
-    ) -> Self 
-    where 
-        for<'a> Dg: serde::de::Deserialize<'a> + Default,
-        Fg: Fn(Dg) -> Gd,
-        for<'a> Dt: serde::de::Deserialize<'a> + Default,
-        Ft: Fn(Dt) -> Td,
-    {
-        let adj = Self::parse_adj_file(
-            adj_file, 
-            adj_gene_header, adj_term_header
-        );
-        let (genes, gene_map) = match gene_data_file {
-            Some(gene_data_file) => Self::parse_scalar_gene_data(
-                gene_data_file, 
-                gene_name_header, gene_data_header,
-                f_gene,
-            ),
-            None => {
-                let mut gene_map = HashMap::new();
-                let gene_names = adj.iter().map(|(_, gene_name)| gene_name.clone()).unique();
-                gene_names.clone().enumerate()
-                    .for_each(|(idx, name)| {
-                        gene_map.insert(name, idx);
-                    });
-
-                (
-                    gene_names.map(|_| f_gene(Dg::default())).collect::<Vec<Gd>>(),
-                    gene_map
-                )
-            },
-        };
-        let (terms, term_map) = match term_data_file {
-            Some(term_data_file) => Self::parse_scalar_term_data(
-                term_data_file, 
-                term_name_header, term_data_header,
-                f_term,
-            ),
-            None => {
-                let mut term_map = HashMap::new();
-                let term_names = adj.iter().map(|(term_name, _)| term_name.clone()).unique();
-                term_names.clone().enumerate()
-                    .for_each(|(idx, name)| {
-                        term_map.insert(name, idx);
-                    });
-
-                (
-                    term_names.map(|_| f_term(Dt::default())).collect::<Vec<Td>>(),
-                    term_map
-                )
-            },
-        };
-        let adj = adj.iter()
-            .map(|(term, gene)| {
-                (term_map[term], gene_map[gene])
-            })
-            .collect::<Vec<(usize, usize)>>();
-
-        Self::from_incidence(genes, terms, adj)
-    }
-
-    fn parse_scalar_gene_data<D, F> (
-        gene_data_file: String, 
-        gene_name_header: Option<String>, gene_data_header: Option<String>,
-        f: F,
-    ) -> (Vec<Gd>, HashMap<String, usize>) 
-    where 
-        for<'a> D: serde::de::Deserialize<'a>,
-        F: Fn(D) -> Gd
-    {
+    /// Build the graph from node data aligned with `gene_names` and `term_names`,
+    /// and (term index, gene index) annotations.
+    pub fn from_named_incidence(
+        gene_names: NameIndex, gene_data: Vec<Gd>,
+        term_names: NameIndex, term_data: Vec<Td>,
+        adj: Vec<(usize, usize)>,
+    ) -> Self {
+        assert_eq!(gene_names.len(), gene_data.len(), "Every gene must have exactly one name");
+        assert_eq!(term_names.len(), term_data.len(), "Every term must have exactly one name");
 
-        assert_eq!(
-            gene_name_header.is_none(),
-            gene_data_header.is_none(),
-            "Headers must be given for either both terms and genes in the adjacency data, or for neither",
-        );
+        let mut arena = Arena::new(gene_data, term_data);
+        adj.iter().for_each(|(term_idx, gene_idx)| arena.annotate(*term_idx, *gene_idx));
 
-        let mut genes = vec![];
-        let mut gene_map = HashMap::new();
-
-        let file = std::fs::File::open(gene_data_file.clone()).
-            unwrap_or_else(|_| 
-                panic!("File {} not found", gene_data_file)
-            );
-
-        if let (Some(name_header), Some(data_header)) = (gene_name_header, gene_data_header) {
-            let mut rdr = csv::ReaderBuilder::new()
-                .has_headers(true)
-                .from_reader(file);
-
-            assert!(
-                rdr.headers().unwrap().iter().any(|header| header == name_header),
-                "File expected to have column {} for gene names, found {:?}",
-                name_header, rdr.headers().unwrap()
-            );
-            assert!(
-                rdr.headers().unwrap().iter().any(|header| header == data_header),
-                "File expected to have column {} for gene data, found {:?}",
-                name_header, rdr.headers().unwrap()
-            );
-            let headers = csv::StringRecord::from(vec![name_header.clone(), data_header.clone()]);
-            rdr.records().enumerate().for_each(|(entry_idx, result)| {
-                let (name, data) = result.unwrap()
-                    .deserialize(Some(&headers))
-                    .unwrap_or_else(|_|
-                        panic!(
-                            "Could not coerce data in columns {}, {} of file {} to (String, Dg)",
-                            name_header, data_header, gene_data_file
-                        )
-                    );
-                if let Some(idx) = gene_map.get(&name) {
-                    println!(
-                        "Warning: gene {} appears in data multiple times (index {} and {}). Skipping repeated entry.", 
-                        name, 
-                        idx, 
-                        entry_idx
-                    )
-                } else {
-                    gene_map.insert(name, genes.len());
-                    genes.push(f(data));
-                }
-            })
-        } else {
-            let mut rdr = csv::ReaderBuilder::new()
-                .has_headers(false)
-                .from_reader(file);
-
-            rdr.deserialize().enumerate().for_each(|(entry_idx, result)| {
-                // let record: (String, Gd) = result
-                let (name, data): (String, D) = result
-                    .unwrap_or_else(|_| 
-                        panic!(
-                            "Could not coerce data in file {} to (String, Dg)",
-                            gene_data_file
-                        )
-                    );
-                if let Some(idx) = gene_map.get(&name) {
-                    println!(
-                        "Warning: gene {} appears in data multiple times (index {} and {}). Skipping repeated entry.", 
-                        name, 
-                        idx, 
-                        entry_idx
-                    )
-                } else {
-                    gene_map.insert(name, genes.len());
-                    genes.push(f(data));
-                }
-            })
+        Self {
+            arena,
+            gene_names,
+            term_namespaces: vec![None; term_names.len()],
+            term_names,
         }
-        
-        (genes, gene_map)
-    }
-    fn parse_scalar_term_data<D, F>(
-        term_data_file: String, 
-        term_name_header: Option<String>, term_data_header: Option<String>,
-        f: F,
-    ) -> (Vec<Td>, HashMap<String, usize>) 
-    where
-        for<'a> D: serde::de::Deserialize<'a>,
-        F: Fn(D) -> Td,
-    {
-
-        assert_eq!(
-            term_name_header.is_none(),
-            term_data_header.is_none(),
-            "Headers must be given for either both terms and genes in the adjacency data, or for neither",
-        );
+    }
 
-        let mut terms = vec![];
-        let mut term_map = HashMap::new();
-
-        let file = std::fs::File::open(term_data_file.clone()).
-            unwrap_or_else(|_| 
-                panic!("File {} not found", term_data_file)
-            );
-            
-        if let (Some(name_header), Some(data_header)) = (term_name_header, term_data_header) {
-            let mut rdr = csv::ReaderBuilder::new()
-                .has_headers(true)
-                .from_reader(file);
-
-            assert!(
-                rdr.headers().unwrap().iter().any(|header| header == name_header),
-                "File expected to have column {} for term names, found {:?}",
-                name_header, rdr.headers().unwrap()
-            );
-            assert!(
-                rdr.headers().unwrap().iter().any(|header| header == data_header),
-                "File expected to have column {} for term data, found {:?}",
-                name_header, rdr.headers().unwrap()
-            );
-            let headers = csv::StringRecord::from(vec![name_header.clone(), data_header.clone()]);
-            rdr.records().enumerate().for_each(|(entry_idx, result)| {
-                let (name, data) = result.unwrap()
-                    .deserialize(Some(&headers))
-                    .unwrap_or_else(|_|
-                        panic!(
-                            "Could not coerce data in columns {}, {} of file {} to (String, D)",
-                            name_header, data_header, term_data_file
-                        )
-                    );
-                if let Some(idx) = term_map.get(&name) {
-                    println!(
-                        "Warning: term {} appears in data multiple times (index {} and {}). Skipping repeated entry.", 
-                        name, 
-                        idx, 
-                        entry_idx
-                    )
-                } else {
-                    term_map.insert(name, terms.len());
-                    terms.push(f(data));
-                }
-            })
-        } else {
-            let mut rdr = csv::ReaderBuilder::new()
-                .has_headers(false)
-                .from_reader(file);
-
-            rdr.deserialize().enumerate().for_each(|(entry_idx, result)| {
-                // let record: (String, Gd) = result
-                let (name, data): (String, D) = result
-                    .unwrap_or_else(|_| 
-                        panic!(
-                            "Could not coerce data in file {} to (String, Td)",
-                            term_data_file
-                        )
-                    );
-                if let Some(idx) = term_map.get(&name) {
-                    println!(
-                        "Warning: term {} appears in data multiple times (index {} and {}). Skipping repeated entry.", 
-                        name, 
-                        idx, 
-                        entry_idx
-                    )
-                } else {
-                    term_map.insert(name, terms.len());
-                    terms.push(f(data));
-                }
-            })
-        }
-        
-        (terms, term_map)
+    /// The ontology with only the terms at `terms`, in that order, and their
+    /// names, namespaces and annotations. Every gene is kept.
+    pub(crate) fn retain_terms(self, terms: &[usize]) -> Self {
+        let groups: Vec<Vec<usize>> = terms.iter().map(|&term_idx| vec![term_idx]).collect();
+        self.merge_terms(&groups)
     }
 
-    fn parse_adj_file(
-        adj_file: String, 
-        adj_term_header: Option<String>,
-        adj_gene_header: Option<String>
-    ) -> Vec<(String, String)> {
-        assert_eq!(
-            adj_term_header.is_none(),
-            adj_gene_header.is_none(),
-            "Headers must be given for either both terms and genes in the adjacency data, or for neither",
+    /// The ontology with one term for each of `groups`, in that order, keeping
+    /// the data, names, namespace and annotations of the first term of each.
+    /// A kept term has the parents of every term in its group, and inherits
+    /// the nearest kept ancestors of dropped parents. Every gene is kept.
+    pub(crate) fn merge_terms(self, groups: &[Vec<usize>]) -> Self {
+        let arena = self.arena;
+        let terms: Vec<usize> = groups.iter().map(|group| group[0]).collect();
+        let mut new_index = vec![None; arena.term_data.len()];
+        groups.iter()
+            .enumerate()
+            .for_each(|(new_idx, group)| group.iter().for_each(|&term_idx| new_index[term_idx] = Some(new_idx)));
+        let parents = arena::bridge_parents(&arena.term_parents, &new_index, groups.len());
+
+        let adj = terms.iter()
+            .enumerate()
+            .flat_map(|(new_idx, &term_idx)| arena.term_genes[term_idx].iter().map(move |&gene_idx| (new_idx, gene_idx)))
+            .collect();
+        let mut term_data: Vec<Option<Td>> = arena.term_data.into_iter().map(Some).collect();
+
+        let mut retained = Self::from_named_incidence(
+            self.gene_names,
+            arena.gene_data,
+            self.term_names.select(&terms),
+            terms.iter()
+                .map(|&term_idx| term_data[term_idx].take().unwrap_or_else(|| panic!("Term {} selected twice", term_idx)))
+                .collect(),
+            adj,
         );
+        retained.term_namespaces = terms.iter().map(|&term_idx| self.term_namespaces[term_idx]).collect();
+        retained.relate_terms(parents);
+        retained
+    }
 
-        let mut adj = vec![];
-        let mut adj_set = HashSet::new();
-
-        let file = std::fs::File::open(adj_file.clone()).
-            unwrap_or_else(|_| 
-                panic!("File {} not found", adj_file)
-            );
-            
-        if let (Some(term_header), Some(gene_header)) = (adj_term_header, adj_gene_header) {
-            let mut rdr = csv::ReaderBuilder::new()
-                .has_headers(true)
-                .from_reader(file);
-
-            assert!(
-                rdr.headers().unwrap().iter().any(|header| header == term_header),
-                "File expected to have column {} for term names, found {:?}",
-                term_header, rdr.headers().unwrap()
-            );
-            assert!(
-                rdr.headers().unwrap().iter().any(|header| header == gene_header),
-                "File expected to have column {} for gene names, found {:?}",
-                gene_header, rdr.headers().unwrap()
-            );
-
-            let headers = csv::StringRecord::from(vec![term_header.clone(), gene_header.clone()]);
-            rdr.records().for_each(|result| {
-                let (term_name, gene_name): (String, String) = result.unwrap()
-                    .deserialize(Some(&headers))
-                    .unwrap_or_else(|_|
-                        panic!(
-                            "Could not coerce data in columns {}, {} of file {} to (String, String)",
-                            term_header, gene_header, adj_file
-                        )
-                    );
-                if adj_set.contains(&(term_name.clone(), gene_name.clone())) {
-                    println!(
-                        "Warning: annotation ({} - {}) appears in adjacency data multiple times. Skipping repeated entry.", 
-                        term_name, 
-                        gene_name
-                    )
-                } else {
-                    adj.push((term_name.clone(), gene_name.clone()));
-                    adj_set.insert((term_name, gene_name));
-                }
-            })
-        } else {
-            let mut rdr = csv::ReaderBuilder::new()
-                .has_headers(false)
-                .from_reader(file);
-
-            rdr.deserialize().for_each(|result| {
-                let (term_name, gene_name): (String, String) = result.unwrap();
-                // if adj.contains(&(term_name.clone(), gene_name.clone())) {
-                //     println!(
-                //         "Warning: annotation ({} - {}) appears in adjacency data multiple times. Skipping repeated entry.", 
-                //         term_name, 
-                //         gene_name
-                //     )
-                // } else {
-                    adj.push((term_name, gene_name));
-                // }
-            });
-        }
-        
-        adj
+    /// Make the terms listed for each term its parents.
+    pub(crate) fn relate_terms(&mut self, parents: Vec<Vec<usize>>) {
+        let arena = &mut self.arena;
+        parents.into_iter()
+            .enumerate()
+            .for_each(|(child_idx, parents)| parents.into_iter().for_each(|parent_idx| {
+                arena.relate(child_idx, parent_idx);
+            }));
     }
+
 }
 
 impl<Td, Gd> GeneSet for GeneOntology<Td, Gd> {
-    type PartNode = Rc<RefCell<genes::Gene<Td, Gd>>>;
-    type WholeNode = Rc<RefCell<terms::Term<Td, Gd>>>;
+    type PartData = Gd;
+    type WholeData = Td;
+    type PartNode<'a> = Gene<'a, Td, Gd> where Self: 'a;
+    type WholeNode<'a> = Term<'a, Td, Gd> where Self: 'a;
 
-    fn iter_parts(&self) -> impl Iterator<Item = Self::PartNode> {
-        self.genes.iter().cloned()
+    fn iter_parts(&self) -> impl Iterator<Item = Self::PartNode<'_>> {
+        self.genes()
     }
-    fn iter_wholes(&self) -> impl Iterator<Item = Self::WholeNode> {
-        self.terms.iter().cloned()
+    fn iter_wholes(&self) -> impl Iterator<Item = Self::WholeNode<'_>> {
+        self.terms()
+    }
+
+    fn n_parts(&self) -> usize { self.arena.gene_data.len() }
+    fn n_wholes(&self) -> usize { self.arena.term_data.len() }
+    fn part(&self, idx: usize) -> Option<Self::PartNode<'_>> {
+        (idx < self.n_parts()).then(|| Gene::new(&self.arena, idx))
+    }
+    fn whole(&self, idx: usize) -> Option<Self::WholeNode<'_>> {
+        (idx < self.n_wholes()).then(|| Term::new(&self.arena, idx))
     }
 
 }
 
+impl<Td, Gd> GeneSetMut for GeneOntology<Td, Gd> {
+    type PartNodeMut<'a> = GeneMut<'a, Gd> where Self: 'a;
+    type WholeNodeMut<'a> = TermMut<'a, Td> where Self: 'a;
+
+    fn part_mut(&mut self, idx: usize) -> Option<Self::PartNodeMut<'_>> {
+        Some(GeneMut::new(self.arena.gene_data.get_mut(idx)?, idx))
+    }
+    fn whole_mut(&mut self, idx: usize) -> Option<Self::WholeNodeMut<'_>> {
+        Some(TermMut::new(self.arena.term_data.get_mut(idx)?, idx))
+    }
+}
 
 impl<Td, Gd> Saveable for GeneOntology<Td, Gd>
 where 
@@ -475,21 +242,26 @@ where
     fn current(&self) -> Self::Output { }
     fn saved(&self) -> Self::Output {}
     fn restore(&mut self) {
-        self.iter_parts().for_each(|gene| gene.borrow_mut().restore());
-        self.iter_wholes().for_each(|term| term.borrow_mut().restore());
+        self.arena.gene_data.iter_mut().for_each(Saveable::restore);
+        self.arena.term_data.iter_mut().for_each(Saveable::restore);
     }
     fn save(&mut self) {
-        self.iter_parts().for_each(|gene| gene.borrow_mut().save());
-        self.iter_wholes().for_each(|term| term.borrow_mut().save());
+        self.arena.gene_data.iter_mut().for_each(Saveable::save);
+        self.arena.term_data.iter_mut().for_each(Saveable::save);
     }
 }
 
-pub struct GOGeneListRolemodel<Td, Gd> 
+/// Gene list Rolemodel over the genes and terms of `G`, by default a
+/// [`GeneOntology`]. Any gene set whose data can be changed and saved, such as
+/// a [`GeneOntGraph`](crate::GeneOntGraph), can be sampled.
+pub struct GOGeneListRolemodel<Td, Gd, G = GeneOntology<Td, Gd>> 
 where 
     Gd: Booleable + Saveable + Activeable,
     Td: Activeable + Saveable,
+    G: GeneSetMut<PartData = Gd, WholeData = Td> + Saveable,
 {
-    gene_ontology: GeneOntology<Td, Gd>,
+    gene_ontology: G,
+    data: PhantomData<(Td, Gd)>,
 
     burn_in: usize,
     nsamples: usize,
@@ -499,34 +271,35 @@ where
     true_active_gene_hit_rate: f64,
     false_inactive_gene_hit_rate: f64,
     illegal_set_penalty: f64,
+
+    posterior_llikelihood: SaveableData<f64>,
 }
 
-impl<Td, Gd> Saveable for GOGeneListRolemodel<Td, Gd>
+impl<Td, Gd, G> Saveable for GOGeneListRolemodel<Td, Gd, G>
 where 
     Gd: Saveable + Booleable + Activeable,
     Td: Activeable + Saveable,
+    G: GeneSetMut<PartData = Gd, WholeData = Td> + Saveable,
 {
     type Output = ();
     fn current(&self) -> Self::Output { }
     fn saved(&self) -> Self::Output { }
     fn restore(&mut self) {
-        self.iter_wholes().for_each(|mut whole| whole.restore());
-        self.iter_parts().for_each(|mut part| part.restore());
+        self.gene_ontology.restore();
     }
     fn save(&mut self) {
-        self.iter_wholes().for_each(|mut whole| whole.save());
-        self.iter_parts().for_each(|mut part| part.save());
-        
+        self.gene_ontology.save();
     }
 }
 
-impl<Td, Gd> GOGeneListRolemodel<Td, Gd>
+impl<Td, Gd, G> GOGeneListRolemodel<Td, Gd, G>
 where 
     Gd: Booleable + Saveable + Activeable,
     Td: Activeable + Saveable,
+    G: GeneSetMut<PartData = Gd, WholeData = Td> + Saveable,
 {
     pub fn new(
-        gene_ontology: GeneOntology<Td, Gd>,
+        gene_ontology: G,
     
         burn_in: usize,
         nsamples: usize,
@@ -539,6 +312,7 @@ where
     ) -> Self {
         Self {
             gene_ontology,
+            data: PhantomData,
             burn_in,
             nsamples,
             thinning,
@@ -546,32 +320,116 @@ where
             true_active_gene_hit_rate,
             false_inactive_gene_hit_rate,
             illegal_set_penalty,
+            posterior_llikelihood: SaveableData::default(),
+        }
+    }
+
+    pub fn gene_ontology(&self) -> &G {
+        &self.gene_ontology
+    }
+}
+
+impl<Td, Gd, G> GOGeneListRolemodel<Td, Gd, G>
+where 
+    Gd: Booleable + Saveable + Activeable,
+    Td: Activeable + Saveable + Clone,
+    G: GeneSetMut<PartData = Gd, WholeData = Td> + Saveable,
+{
+    /// Capture the chain after `iteration` steps, with `rng` as it will be
+    /// used for the next step.
+    pub fn checkpoint(&self, iteration: usize, rng: &ChainRng) -> Checkpoint<Td, Gd> {
+        Checkpoint {
+            iteration,
+            rng: rng.clone(),
+            burn_in: self.burn_in,
+            nsamples: self.nsamples,
+            thinning: self.thinning,
+            hyperparameters: BTreeMap::from([
+                ("set_activity_probability".to_string(), self.set_activity_probability),
+                ("true_active_gene_hit_rate".to_string(), self.true_active_gene_hit_rate),
+                ("false_inactive_gene_hit_rate".to_string(), self.false_inactive_gene_hit_rate),
+                ("illegal_set_penalty".to_string(), self.illegal_set_penalty),
+            ]),
+            posterior_llikelihood: self.posterior_llikelihood,
+            term_data: self.gene_ontology.iter_wholes().map(|term| term.data().clone()).collect(),
+            gene_data: self.gene_ontology.iter_parts().map(|gene| *gene.data()).collect(),
         }
     }
+
+    /// Restore the state captured in `checkpoint`. Continue the chain by running
+    /// steps `checkpoint.iteration + 1` onwards with `checkpoint.rng`.
+    pub fn resume(&mut self, checkpoint: &Checkpoint<Td, Gd>) {
+        assert_eq!(
+            checkpoint.term_data.len(), self.gene_ontology.n_wholes(),
+            "Checkpoint has {} terms, model has {}", checkpoint.term_data.len(), self.gene_ontology.n_wholes()
+        );
+        assert_eq!(
+            checkpoint.gene_data.len(), self.gene_ontology.n_parts(),
+            "Checkpoint has {} genes, model has {}", checkpoint.gene_data.len(), self.gene_ontology.n_parts()
+        );
+        let hyperparameter = |name: &str| *checkpoint.hyperparameters.get(name)
+            .unwrap_or_else(|| panic!("Checkpoint is missing hyperparameter {}", name));
+
+        self.burn_in = checkpoint.burn_in;
+        self.nsamples = checkpoint.nsamples;
+        self.thinning = checkpoint.thinning;
+        self.set_activity_probability = hyperparameter("set_activity_probability");
+        self.true_active_gene_hit_rate = hyperparameter("true_active_gene_hit_rate");
+        self.false_inactive_gene_hit_rate = hyperparameter("false_inactive_gene_hit_rate");
+        self.illegal_set_penalty = hyperparameter("illegal_set_penalty");
+        self.posterior_llikelihood = checkpoint.posterior_llikelihood;
+
+        checkpoint.term_data.iter()
+            .enumerate()
+            .for_each(|(idx, data)| *self.gene_ontology.whole_mut(idx).unwrap().data_mut() = data.clone());
+        checkpoint.gene_data.iter()
+            .enumerate()
+            .for_each(|(idx, data)| *self.gene_ontology.part_mut(idx).unwrap().data_mut() = *data);
+    }
 }
 
-impl<Td, Gd> GeneSet for GOGeneListRolemodel<Td, Gd> 
+impl<Td, Gd, G> GeneSet for GOGeneListRolemodel<Td, Gd, G> 
 where 
     Gd: Booleable + Saveable + Activeable,
     Td: Activeable + Saveable,
+    G: GeneSetMut<PartData = Gd, WholeData = Td> + Saveable,
 {
-    type PartNode = Rc<RefCell<genes::Gene<Td, Gd>>>;
-    type WholeNode = Rc<RefCell<terms::Term<Td, Gd>>>;
+    type PartData = Gd;
+    type WholeData = Td;
+    type PartNode<'a> = G::PartNode<'a> where Self: 'a;
+    type WholeNode<'a> = G::WholeNode<'a> where Self: 'a;
 
-    fn iter_parts(&self) -> impl Iterator<Item = Self::PartNode> {
-        self.gene_ontology.genes.iter().cloned()
+    fn iter_parts(&self) -> impl Iterator<Item = Self::PartNode<'_>> {
+        self.gene_ontology.iter_parts()
     }
-    fn iter_wholes(&self) -> impl Iterator<Item = Self::WholeNode> {
-        self.gene_ontology.terms.iter().cloned()
+    fn iter_wholes(&self) -> impl Iterator<Item = Self::WholeNode<'_>> {
+        self.gene_ontology.iter_wholes()
     }
+
+    fn n_parts(&self) -> usize { self.gene_ontology.n_parts() }
+    fn n_wholes(&self) -> usize { self.gene_ontology.n_wholes() }
+    fn part(&self, idx: usize) -> Option<Self::PartNode<'_>> { self.gene_ontology.part(idx) }
+    fn whole(&self, idx: usize) -> Option<Self::WholeNode<'_>> { self.gene_ontology.whole(idx) }
 }
 
-impl<Td, Gd> GeneListRolemodel for GOGeneListRolemodel<Td, Gd> 
+impl<Td, Gd, G> GeneSetMut for GOGeneListRolemodel<Td, Gd, G> 
+where 
+    Gd: Booleable + Saveable + Activeable,
+    Td: Activeable + Saveable,
+    G: GeneSetMut<PartData = Gd, WholeData = Td> + Saveable,
+{
+    type PartNodeMut<'a> = G::PartNodeMut<'a> where Self: 'a;
+    type WholeNodeMut<'a> = G::WholeNodeMut<'a> where Self: 'a;
+
+    fn part_mut(&mut self, idx: usize) -> Option<Self::PartNodeMut<'_>> { self.gene_ontology.part_mut(idx) }
+    fn whole_mut(&mut self, idx: usize) -> Option<Self::WholeNodeMut<'_>> { self.gene_ontology.whole_mut(idx) }
+}
+
+impl<Td, Gd, G> GeneListRolemodel for GOGeneListRolemodel<Td, Gd, G> 
 where 
-    Self::PartNode: Node<Data = Gd, NeighborType = Rc<RefCell<Term<Td, Gd>>>> + Part,
-    Self::WholeNode: Node<Data = Td, NeighborType = Rc<RefCell<Gene<Td, Gd>>>> + Whole,
     Gd: Saveable + Booleable + Activeable,
     Td: Saveable + Activeable,
+    G: GeneSetMut<PartData = Gd, WholeData = Td> + Saveable,
 {
     fn set_activity_probability(&self) -> f64 {
         self.set_activity_probability
@@ -599,7 +457,10 @@ where
         self.thinning
     }
     
-    fn posterior_llikelihood(&self) -> &crate::SaveableData<f64> {
-        todo!()
+    fn posterior_llikelihood(&self) -> &SaveableData<f64> {
+        &self.posterior_llikelihood
+    }
+    fn posterior_llikelihood_mut(&mut self) -> &mut SaveableData<f64> {
+        &mut self.posterior_llikelihood
     }
 }
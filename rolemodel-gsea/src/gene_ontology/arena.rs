@@ -0,0 +1,184 @@
+/// Node data and adjacency of a [`GeneOntology`](crate::GeneOntology), addressed by index.
+///
+/// Genes and terms refer to each other by index rather than owning each other,
+/// so the graph has no reference cycles. Handles borrow the arena, so node
+/// data is only changed while no handle is alive.
+#[derive(Debug)]
+pub(crate) struct Arena<Td, Gd> {
+    pub(crate) gene_data: Vec<Gd>,
+    pub(crate) term_data: Vec<Td>,
+    /// Indices of the terms annotating each gene.
+    pub(crate) gene_terms: Vec<Vec<usize>>,
+    /// Indices of the genes annotated to each term.
+    pub(crate) term_genes: Vec<Vec<usize>>,
+    /// Indices of the terms directly above each term in the term hierarchy.
+    pub(crate) term_parents: Vec<Vec<usize>>,
+    /// Indices of the terms directly below each term in the term hierarchy.
+    pub(crate) term_children: Vec<Vec<usize>>,
+}
+
+impl<Td, Gd> Arena<Td, Gd> {
+    pub(crate) fn new(gene_data: Vec<Gd>, term_data: Vec<Td>) -> Self {
+        Self {
+            gene_terms: vec![Vec::new(); gene_data.len()],
+            term_genes: vec![Vec::new(); term_data.len()],
+            term_parents: vec![Vec::new(); term_data.len()],
+            term_children: vec![Vec::new(); term_data.len()],
+            gene_data,
+            term_data,
+        }
+    }
+
+    pub(crate) fn annotate(&mut self, term_idx: usize, gene_idx: usize) {
+        assert!(term_idx < self.term_data.len(), "Term {} out of range for {} terms", term_idx, self.term_data.len());
+        assert!(gene_idx < self.gene_data.len(), "Gene {} out of range for {} genes", gene_idx, self.gene_data.len());
+        self.term_genes[term_idx].push(gene_idx);
+        self.gene_terms[gene_idx].push(term_idx);
+    }
+
+    pub(crate) fn add_gene(&mut self, data: Gd) -> usize {
+        self.gene_data.push(data);
+        self.gene_terms.push(Vec::new());
+        self.gene_data.len() - 1
+    }
+    pub(crate) fn add_term(&mut self, data: Td) -> usize {
+        self.term_data.push(data);
+        self.term_genes.push(Vec::new());
+        self.term_parents.push(Vec::new());
+        self.term_children.push(Vec::new());
+        self.term_data.len() - 1
+    }
+
+    /// Remove one annotation of gene `gene_idx` to term `term_idx`, returning
+    /// whether there was one.
+    pub(crate) fn remove_annotation(&mut self, term_idx: usize, gene_idx: usize) -> bool {
+        assert!(term_idx < self.term_data.len(), "Term {} out of range for {} terms", term_idx, self.term_data.len());
+        assert!(gene_idx < self.gene_data.len(), "Gene {} out of range for {} genes", gene_idx, self.gene_data.len());
+        let Some(position) = self.term_genes[term_idx].iter().position(|&idx| idx == gene_idx) else {
+            return false
+        };
+        self.term_genes[term_idx].remove(position);
+        let position = self.gene_terms[gene_idx].iter()
+            .position(|&idx| idx == term_idx)
+            .unwrap_or_else(|| panic!("Term {} lists gene {}, but the gene does not list the term", term_idx, gene_idx));
+        self.gene_terms[gene_idx].remove(position);
+        true
+    }
+
+    /// Remove gene `gene_idx` and its annotations. Later genes move down one index.
+    pub(crate) fn remove_gene(&mut self, gene_idx: usize) -> Gd {
+        assert!(gene_idx < self.gene_data.len(), "Gene {} out of range for {} genes", gene_idx, self.gene_data.len());
+        self.gene_terms.remove(gene_idx).into_iter()
+            .for_each(|term_idx| self.term_genes[term_idx].retain(|&idx| idx != gene_idx));
+        self.term_genes.iter_mut()
+            .flatten()
+            .filter(|idx| **idx > gene_idx)
+            .for_each(|idx| *idx -= 1);
+        self.gene_data.remove(gene_idx)
+    }
+    /// Remove term `term_idx` and its annotations, making its children
+    /// children of its parents. Later terms move down one index.
+    pub(crate) fn remove_term(&mut self, term_idx: usize) -> Td {
+        assert!(term_idx < self.term_data.len(), "Term {} out of range for {} terms", term_idx, self.term_data.len());
+        let (parents, children) = (self.term_parents[term_idx].clone(), self.term_children[term_idx].clone());
+        children.iter().for_each(|&child_idx| {
+            self.unrelate(child_idx, term_idx);
+            parents.iter().for_each(|&parent_idx| {
+                self.relate(child_idx, parent_idx);
+            });
+        });
+        parents.iter().for_each(|&parent_idx| {
+            self.unrelate(term_idx, parent_idx);
+        });
+
+        self.term_genes.remove(term_idx).into_iter()
+            .for_each(|gene_idx| self.gene_terms[gene_idx].retain(|&idx| idx != term_idx));
+        self.term_parents.remove(term_idx);
+        self.term_children.remove(term_idx);
+        self.gene_terms.iter_mut()
+            .chain(self.term_parents.iter_mut())
+            .chain(self.term_children.iter_mut())
+            .flatten()
+            .filter(|idx| **idx > term_idx)
+            .for_each(|idx| *idx -= 1);
+        self.term_data.remove(term_idx)
+    }
+
+    /// Make term `parent_idx` a parent of term `child_idx`, returning whether
+    /// the relation is new.
+    pub(crate) fn relate(&mut self, child_idx: usize, parent_idx: usize) -> bool {
+        assert!(child_idx < self.term_data.len(), "Term {} out of range for {} terms", child_idx, self.term_data.len());
+        assert!(parent_idx < self.term_data.len(), "Term {} out of range for {} terms", parent_idx, self.term_data.len());
+        assert_ne!(child_idx, parent_idx, "Term {} cannot be its own parent", child_idx);
+        if self.term_parents[child_idx].contains(&parent_idx) {
+            return false
+        }
+        self.term_parents[child_idx].push(parent_idx);
+        self.term_children[parent_idx].push(child_idx);
+        true
+    }
+    /// Remove term `parent_idx` from the parents of term `child_idx`,
+    /// returning whether it was one.
+    pub(crate) fn unrelate(&mut self, child_idx: usize, parent_idx: usize) -> bool {
+        assert!(child_idx < self.term_data.len(), "Term {} out of range for {} terms", child_idx, self.term_data.len());
+        assert!(parent_idx < self.term_data.len(), "Term {} out of range for {} terms", parent_idx, self.term_data.len());
+        let Some(position) = self.term_parents[child_idx].iter().position(|&idx| idx == parent_idx) else {
+            return false
+        };
+        self.term_parents[child_idx].remove(position);
+        self.term_children[parent_idx].retain(|&idx| idx != child_idx);
+        true
+    }
+}
+
+/// Parents of the terms kept when some terms are dropped or merged.
+///
+/// `new_index` gives the index each old term is kept under, if any, and
+/// `parents` the parents of each old term. A kept term inherits the parents of
+/// every old term kept under its index; where such a parent was dropped, the
+/// nearest kept terms above it take its place, so no ancestor is lost.
+pub(crate) fn bridge_parents(parents: &[Vec<usize>], new_index: &[Option<usize>], nkept: usize) -> Vec<Vec<usize>> {
+    let mut bridged: Vec<Vec<usize>> = vec![Vec::new(); nkept];
+    new_index.iter()
+        .enumerate()
+        .filter_map(|(old_idx, new_idx)| Some((old_idx, (*new_idx)?)))
+        .for_each(|(old_idx, new_idx)| {
+            let mut visited = vec![old_idx];
+            let mut stack: Vec<usize> = parents[old_idx].iter().rev().copied().collect();
+            while let Some(parent_idx) = stack.pop() {
+                if visited.contains(&parent_idx) {
+                    continue
+                }
+                visited.push(parent_idx);
+                match new_index[parent_idx] {
+                    Some(kept_idx) if kept_idx != new_idx => {
+                        if !bridged[new_idx].contains(&kept_idx) {
+                            bridged[new_idx].push(kept_idx);
+                        }
+                    },
+                    Some(_) => {},
+                    None => stack.extend(parents[parent_idx].iter().rev()),
+                }
+            }
+        });
+    bridged
+}
+
+/// A term on a cycle of the hierarchy given by the `parents` of each term, if
+/// there is one. Terms left after repeatedly removing those without remaining
+/// children lie on a cycle.
+pub(crate) fn cycle_term(parents: &[Vec<usize>]) -> Option<usize> {
+    let nterms = parents.len();
+    let mut nchildren = vec![0; nterms];
+    parents.iter().flatten().for_each(|&parent_idx| nchildren[parent_idx] += 1);
+    let mut leaves: Vec<usize> = (0..nterms).filter(|&idx| nchildren[idx] == 0).collect();
+    while let Some(term_idx) = leaves.pop() {
+        parents[term_idx].iter().for_each(|&parent_idx| {
+            nchildren[parent_idx] -= 1;
+            if nchildren[parent_idx] == 0 {
+                leaves.push(parent_idx);
+            }
+        });
+    }
+    (0..nterms).find(|&idx| nchildren[idx] > 0)
+}
@@ -0,0 +1,210 @@
+//! Compact binary files holding a whole [`GeneOntology`].
+//!
+//! A file starts with the magic bytes `RMGO`, the format version, the CRC-32
+//! checksum and the length of the payload, all little-endian. The payload holds
+//! the gene and term names and descriptions, the aliases of merged terms, the term namespaces, the node data,
+//! the (term, gene) annotations and the (child, parent) relations of the term
+//! hierarchy, encoded with postcard.
+
+use crate::gene_ontology::{arena::cycle_term, names::NameIndex, namespace::Namespace, GeneOntology};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+const MAGIC: &[u8; 4] = b"RMGO";
+/// Version of the payload layout, increased whenever it changes.
+const FORMAT_VERSION: u32 = 4;
+
+#[derive(Serialize)]
+struct PayloadRef<'a, Td, Gd> {
+    gene_names: Vec<&'a str>,
+    gene_descriptions: Vec<Option<&'a str>>,
+    term_names: Vec<&'a str>,
+    term_descriptions: Vec<Option<&'a str>>,
+    term_aliases: Vec<&'a [String]>,
+    term_namespaces: &'a [Option<Namespace>],
+    gene_data: &'a [Gd],
+    term_data: &'a [Td],
+    adjacency: Vec<(usize, usize)>,
+    term_relations: Vec<(usize, usize)>,
+}
+
+#[derive(Deserialize)]
+struct Payload<Td, Gd> {
+    gene_names: Vec<String>,
+    gene_descriptions: Vec<Option<String>>,
+    term_names: Vec<String>,
+    term_descriptions: Vec<Option<String>>,
+    term_aliases: Vec<Vec<String>>,
+    term_namespaces: Vec<Option<Namespace>>,
+    gene_data: Vec<Gd>,
+    term_data: Vec<Td>,
+    adjacency: Vec<(usize, usize)>,
+    term_relations: Vec<(usize, usize)>,
+}
+
+pub(crate) fn invalid_data(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+/// Write `payload` after `magic`, `version`, its CRC-32 checksum and its length.
+pub(crate) fn write_framed(writer: &mut impl Write, magic: &[u8; 4], version: u32, payload: &[u8]) -> std::io::Result<()> {
+    writer.write_all(magic)?;
+    writer.write_all(&version.to_le_bytes())?;
+    writer.write_all(&crc32fast::hash(payload).to_le_bytes())?;
+    writer.write_all(&(payload.len() as u64).to_le_bytes())?;
+    writer.write_all(payload)?;
+    writer.flush()
+}
+
+/// Read a payload written by [`write_framed`]. Fails with
+/// [`std::io::ErrorKind::InvalidData`] naming the file `kind` if it has other
+/// magic bytes or another version, or does not match its checksum.
+pub(crate) fn read_framed(mut reader: impl Read, magic: &[u8; 4], version: u32, kind: &str) -> std::io::Result<Vec<u8>> {
+    let mut header = [0; 20];
+    reader.read_exact(&mut header)?;
+    if &header[0..4] != magic {
+        return Err(invalid_data(format!("Not a {}", kind.to_lowercase())))
+    }
+    let found_version = u32::from_le_bytes(header[4..8].try_into().unwrap());
+    if found_version != version {
+        return Err(invalid_data(format!(
+            "{} has format version {}, expected {}", kind, found_version, version
+        )))
+    }
+    let checksum = u32::from_le_bytes(header[8..12].try_into().unwrap());
+    let len = u64::from_le_bytes(header[12..20].try_into().unwrap());
+
+    let mut payload = Vec::new();
+    reader.take(len).read_to_end(&mut payload)?;
+    if payload.len() as u64 != len {
+        return Err(invalid_data(format!(
+            "{} is truncated: got {} of {} bytes", kind, payload.len(), len
+        )))
+    }
+    if crc32fast::hash(&payload) != checksum {
+        return Err(invalid_data(format!("{} does not match its checksum", kind)))
+    }
+    Ok(payload)
+}
+
+fn descriptions(names: &NameIndex) -> Vec<Option<&str>> {
+    (0..names.len()).map(|idx| names.description(idx)).collect()
+}
+
+fn aliases(names: &NameIndex) -> Vec<&[String]> {
+    (0..names.len()).map(|idx| names.aliases(idx)).collect()
+}
+
+fn name_index(names: Vec<String>, descriptions: Vec<Option<String>>) -> std::io::Result<NameIndex> {
+    let mut index = NameIndex::try_new(names).map_err(invalid_data)?;
+    descriptions.into_iter()
+        .enumerate()
+        .filter_map(|(idx, description)| Some((idx, description?)))
+        .for_each(|(idx, description)| index.set_description(idx, description));
+    Ok(index)
+}
+
+impl<Td, Gd> GeneOntology<Td, Gd>
+where
+    Td: Serialize + DeserializeOwned,
+    Gd: Serialize + DeserializeOwned,
+{
+    /// Write the ontology as a versioned, checksummed binary file.
+    ///
+    /// The genes of each term keep their order; the terms of each gene are
+    /// restored in term index order.
+    pub fn write_binary(&self, mut writer: impl Write) -> std::io::Result<()> {
+        let adjacency = self.arena.term_genes.iter()
+            .enumerate()
+            .flat_map(|(term_idx, genes)| genes.iter().map(move |gene_idx| (term_idx, *gene_idx)))
+            .collect();
+
+        let term_relations = self.arena.term_parents.iter()
+            .enumerate()
+            .flat_map(|(child_idx, parents)| parents.iter().map(move |parent_idx| (child_idx, *parent_idx)))
+            .collect();
+
+        let payload = postcard::to_stdvec(&PayloadRef {
+            gene_names: self.gene_names.iter().collect(),
+            gene_descriptions: descriptions(&self.gene_names),
+            term_names: self.term_names.iter().collect(),
+            term_descriptions: descriptions(&self.term_names),
+            term_aliases: aliases(&self.term_names),
+            term_namespaces: &self.term_namespaces,
+            gene_data: &self.arena.gene_data,
+            term_data: &self.arena.term_data,
+            adjacency,
+            term_relations,
+        }).map_err(|err| invalid_data(format!("Could not encode gene ontology: {}", err)))?;
+
+        write_framed(&mut writer, MAGIC, FORMAT_VERSION, &payload)
+    }
+
+    /// Read an ontology written by [`GeneOntology::write_binary`]. Fails with
+    /// [`std::io::ErrorKind::InvalidData`] if the file is not a gene ontology,
+    /// has another format version, does not match its checksum, or holds an
+    /// ontology that could not have been written: indices out of range,
+    /// repeated names or aliases, or a cycle in the term hierarchy.
+    pub fn read_binary(reader: impl Read) -> std::io::Result<Self> {
+        let payload = read_framed(reader, MAGIC, FORMAT_VERSION, "Binary gene ontology")?;
+
+        let payload: Payload<Td, Gd> = postcard::from_bytes(&payload)
+            .map_err(|err| invalid_data(format!("Could not decode gene ontology: {}", err)))?;
+        if payload.gene_names.len() != payload.gene_data.len() || payload.gene_descriptions.len() != payload.gene_data.len()
+            || payload.term_names.len() != payload.term_data.len() || payload.term_descriptions.len() != payload.term_data.len()
+            || payload.term_aliases.len() != payload.term_data.len() || payload.term_namespaces.len() != payload.term_data.len()
+        {
+            return Err(invalid_data("Binary gene ontology has names and data of different lengths".to_string()))
+        }
+        if let Some((term_idx, gene_idx)) = payload.adjacency.iter()
+            .find(|(term_idx, gene_idx)| *term_idx >= payload.term_data.len() || *gene_idx >= payload.gene_data.len())
+        {
+            return Err(invalid_data(format!("Annotation of term {} to gene {} is out of range", term_idx, gene_idx)))
+        }
+        if let Some((child_idx, parent_idx)) = payload.term_relations.iter()
+            .find(|(child_idx, parent_idx)| {
+                *child_idx >= payload.term_data.len() || *parent_idx >= payload.term_data.len() || child_idx == parent_idx
+            })
+        {
+            return Err(invalid_data(format!("Term {} cannot have term {} as a parent", child_idx, parent_idx)))
+        }
+
+        let mut parents = vec![Vec::new(); payload.term_data.len()];
+        payload.term_relations.into_iter().for_each(|(child_idx, parent_idx)| parents[child_idx].push(parent_idx));
+        if let Some(term_idx) = cycle_term(&parents) {
+            return Err(invalid_data(format!("Term hierarchy has a cycle through term {}", payload.term_names[term_idx])))
+        }
+
+        let gene_names = name_index(payload.gene_names, payload.gene_descriptions)?;
+        let mut term_names = name_index(payload.term_names, payload.term_descriptions)?;
+        for (idx, aliases) in payload.term_aliases.into_iter().enumerate() {
+            for alias in aliases {
+                term_names.try_add_alias(idx, alias).map_err(invalid_data)?;
+            }
+        }
+
+        let mut gene_ontology = Self::from_named_incidence(
+            gene_names,
+            payload.gene_data,
+            term_names,
+            payload.term_data,
+            payload.adjacency,
+        );
+        gene_ontology.term_namespaces = payload.term_namespaces;
+        gene_ontology.relate_terms(parents);
+        Ok(gene_ontology)
+    }
+
+    pub fn save_binary(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        self.write_binary(BufWriter::new(File::create(path)?))
+    }
+    pub fn load_binary(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Self::read_binary(BufReader::new(File::open(path)?))
+    }
+}
@@ -0,0 +1,75 @@
+//! Merging terms that annotate exactly the same genes.
+//!
+//! Such terms cannot be told apart by the Rolemodel, which splits their
+//! posterior activity between them arbitrarily. Collapsing keeps one node per
+//! gene set and remembers the other IDs as aliases of its name.
+
+use crate::gene_ontology::GeneOntology;
+
+use hashbrown::HashMap;
+
+/// What [`GeneOntology::collapse_identical_terms`] merged.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CollapseReport {
+    /// Terms before collapsing.
+    pub total_terms: usize,
+    /// Terms after collapsing.
+    pub kept_terms: usize,
+    /// IDs of every group of two or more merged terms, the kept term first.
+    pub groups: Vec<Vec<String>>,
+}
+
+impl<Td, Gd> GeneOntology<Td, Gd> {
+    /// Rebuild the ontology with a single term for each set of genes.
+    ///
+    /// Terms are merged when they have the same genes and the same namespace.
+    /// The first term of a group keeps its data, description and namespace and
+    /// can be found under the IDs of the others, which become its aliases.
+    /// It takes over the parents of the others, and their children.
+    /// Terms without genes are never merged.
+    pub fn collapse_identical_terms(self) -> (Self, CollapseReport) {
+        let n_terms = self.arena.term_data.len();
+        let mut group_of_genes = HashMap::new();
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        (0..n_terms).for_each(|idx| {
+            let mut genes = self.arena.term_genes[idx].clone();
+            if genes.is_empty() {
+                groups.push(vec![idx]);
+                return
+            }
+            genes.sort_unstable();
+            genes.dedup();
+            let group_idx = *group_of_genes.entry((self.term_namespace(idx), genes)).or_insert(groups.len());
+            if group_idx == groups.len() {
+                groups.push(Vec::new());
+            }
+            groups[group_idx].push(idx);
+        });
+
+        let report = CollapseReport {
+            total_terms: n_terms,
+            kept_terms: groups.len(),
+            groups: groups.iter()
+                .filter(|group| group.len() > 1)
+                .map(|group| group.iter()
+                    .map(|&idx| self.term_names.name(idx).unwrap_or_default().to_string())
+                    .collect())
+                .collect(),
+        };
+
+        let merged: Vec<Vec<String>> = groups.iter()
+            .map(|group| group[1..].iter()
+                .flat_map(|&idx| std::iter::once(self.term_names.name(idx).unwrap_or_default())
+                    .chain(self.term_names.aliases(idx).iter().map(|alias| alias.as_str())))
+                .map(|name| name.to_string())
+                .collect())
+            .collect();
+
+        let mut collapsed = self.merge_terms(&groups);
+        merged.into_iter()
+            .enumerate()
+            .for_each(|(idx, aliases)| aliases.into_iter().for_each(|alias| collapsed.term_names.add_alias(idx, alias)));
+
+        (collapsed, report)
+    }
+}
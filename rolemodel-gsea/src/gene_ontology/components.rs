@@ -0,0 +1,306 @@
+//! Connected components of the gene-term graph, and sampling them one at a time.
+//!
+//! Terms that share no genes, directly or through other terms, are
+//! conditionally independent under the Rolemodel. Each component can then run
+//! its own, shorter chain, optionally on its own thread, and the chains merge
+//! into draws and a posterior summary of the whole ontology.
+
+use crate::{
+    gene_ontology::{GeneOntology, GOGeneListRolemodel},
+    rolemodel::{
+        checkpoint::ChainRng,
+        posterior::PosteriorSummary,
+        saveable::{Saveable, SaveableData},
+        trace::Draw,
+        Rolemodel,
+    },
+    Activeable, GeneSet, Node,
+};
+use rand::{Rng, SeedableRng};
+use rv::data::Booleable;
+
+use std::{
+    sync::Mutex,
+    thread,
+};
+
+/// Genes and terms connected to each other through annotations, by index in
+/// increasing order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Component {
+    pub genes: Vec<usize>,
+    pub terms: Vec<usize>,
+}
+
+impl<Td, Gd> GeneOntology<Td, Gd> {
+    /// The connected components of the gene-term graph.
+    ///
+    /// Every gene and term is in exactly one component; an unannotated gene or
+    /// a term without genes forms a component of its own. Components with terms
+    /// come first, ordered by their lowest term, then unannotated genes by index.
+    pub fn connected_components(&self) -> Vec<Component> {
+        let arena = &self.arena;
+        let mut gene_seen = vec![false; arena.gene_data.len()];
+        let mut term_seen = vec![false; arena.term_data.len()];
+        let mut components = Vec::new();
+
+        for start in 0..arena.term_data.len() {
+            if term_seen[start] {
+                continue
+            }
+            term_seen[start] = true;
+            let mut component = Component::default();
+            let mut stack = vec![start];
+            while let Some(term_idx) = stack.pop() {
+                component.terms.push(term_idx);
+                for &gene_idx in &arena.term_genes[term_idx] {
+                    if gene_seen[gene_idx] {
+                        continue
+                    }
+                    gene_seen[gene_idx] = true;
+                    component.genes.push(gene_idx);
+                    arena.gene_terms[gene_idx].iter().for_each(|&term_idx| {
+                        if !term_seen[term_idx] {
+                            term_seen[term_idx] = true;
+                            stack.push(term_idx);
+                        }
+                    });
+                }
+            }
+            component.genes.sort_unstable();
+            component.terms.sort_unstable();
+            components.push(component);
+        }
+
+        gene_seen.iter()
+            .enumerate()
+            .filter(|(_, &seen)| !seen)
+            .for_each(|(gene_idx, _)| components.push(Component { genes: vec![gene_idx], terms: vec![] }));
+        components
+    }
+
+    /// The genes and terms of `component` as an ontology of their own, in the
+    /// order listed there, with their names, namespaces and annotations.
+    ///
+    /// Panics if a term of `component` annotates a gene outside it.
+    pub fn component_ontology(&self, component: &Component) -> Self
+    where
+        Td: Clone,
+        Gd: Clone,
+    {
+        let local_genes = self.local_gene_indices([component]);
+        component.terms.iter().for_each(|&term_idx| {
+            if let Some(gene_idx) = self.arena.term_genes[term_idx].iter().find(|&&gene_idx| local_genes[gene_idx].is_none()) {
+                panic!("Term {} annotates gene {}, which is not in its component", term_idx, gene_idx)
+            }
+        });
+        self.induced_subgraph(&component.genes, &component.terms)
+    }
+
+    /// Position of every gene within the component listing it.
+    fn local_gene_indices<'a>(&self, components: impl IntoIterator<Item = &'a Component>) -> Vec<Option<usize>> {
+        let mut local_genes = vec![None; self.arena.gene_data.len()];
+        components.into_iter().for_each(|component| {
+            component.genes.iter()
+                .enumerate()
+                .for_each(|(local_idx, &gene_idx)| local_genes[gene_idx] = Some(local_idx));
+        });
+        local_genes
+    }
+
+    fn component_data(&self, component: &Component, local_genes: &[Option<usize>]) -> ComponentData<Td, Gd>
+    where
+        Td: Clone,
+        Gd: Clone,
+    {
+        ComponentData {
+            gene_data: component.genes.iter().map(|&idx| self.arena.gene_data[idx].clone()).collect(),
+            term_data: component.terms.iter().map(|&idx| self.arena.term_data[idx].clone()).collect(),
+            adj: component.terms.iter()
+                .enumerate()
+                .flat_map(|(local_idx, &term_idx)| self.arena.term_genes[term_idx].iter().map(move |&gene_idx| {
+                    let local_gene = local_genes[gene_idx]
+                        .unwrap_or_else(|| panic!("Term {} annotates gene {}, which is not in its component", term_idx, gene_idx));
+                    (local_idx, local_gene)
+                }))
+                .collect(),
+        }
+    }
+}
+
+/// Node data and local annotations of one component, ready to be sent to the
+/// thread that samples it.
+struct ComponentData<Td, Gd> {
+    gene_data: Vec<Gd>,
+    term_data: Vec<Td>,
+    adj: Vec<(usize, usize)>,
+}
+
+/// A finished component chain.
+struct ComponentRun<Td, Gd> {
+    summary: PosteriorSummary,
+    /// Iteration, log posterior and local active terms of every retained draw.
+    draws: Vec<(usize, f64, Vec<usize>)>,
+    log_posterior: f64,
+    gene_data: Vec<Gd>,
+    term_data: Vec<Td>,
+}
+
+/// Everything but the ontology that a component chain shares with the whole model.
+#[derive(Clone, Copy)]
+struct ChainSettings {
+    burn_in: usize,
+    nsamples: usize,
+    thinning: usize,
+    set_activity_probability: f64,
+    true_active_gene_hit_rate: f64,
+    false_inactive_gene_hit_rate: f64,
+    illegal_set_penalty: f64,
+}
+
+impl ChainSettings {
+    fn run<Td, Gd>(&self, data: ComponentData<Td, Gd>, mut rng: ChainRng) -> ComponentRun<Td, Gd>
+    where
+        Gd: Booleable + Saveable + Activeable,
+        Td: Activeable + Saveable + Clone,
+    {
+        let mut model = GOGeneListRolemodel::new(
+            GeneOntology::from_incidence(data.gene_data, data.term_data, data.adj),
+            self.burn_in,
+            self.nsamples,
+            self.thinning,
+            self.set_activity_probability,
+            self.true_active_gene_hit_rate,
+            self.false_inactive_gene_hit_rate,
+            self.illegal_set_penalty,
+        );
+        let mut summary = PosteriorSummary::new(model.n_wholes(), model.n_parts());
+        let mut draws = Vec::with_capacity(self.nsamples);
+        model.draw_samples_with(&mut rng, |model, draw| {
+            summary.add_draw(
+                model.iter_wholes().map(|term| term.data().is_active()),
+                model.iter_parts().map(|gene| gene.data().is_active()),
+            );
+            draws.push((draw.iteration, draw.log_posterior, draw.active_terms.clone()));
+        });
+
+        ComponentRun {
+            summary,
+            draws,
+            log_posterior: Rolemodel::posterior_llikelihood(&model),
+            gene_data: model.gene_ontology.genes().map(|gene| *gene.data()).collect(),
+            term_data: model.gene_ontology.terms().map(|term| term.data().clone()).collect(),
+        }
+    }
+}
+
+impl<Td, Gd> GOGeneListRolemodel<Td, Gd>
+where
+    Gd: Booleable + Saveable + Activeable + Send,
+    Td: Activeable + Saveable + Clone + Send,
+{
+    /// Run a separate chain on each connected component of the ontology and
+    /// merge them, returning the posterior summary of the whole ontology.
+    ///
+    /// Every component chain has the burn-in, number of samples, thinning and
+    /// hyperparameters of this model. Chains are seeded from `rng` in component
+    /// order, so the result is the same with or without `parallel`, which runs
+    /// them on all available threads. Unannotated genes share a single chain.
+    ///
+    /// Draw `k` of the whole model joins draw `k` of every component: its active
+    /// terms are their union and its log posterior their sum. Merged draws are
+    /// passed to `on_draw` once every chain is done, and the model is left in
+    /// the final state of the component chains.
+    ///
+    /// Only the default, [`GeneOntology`]-backed model has this method, as the
+    /// components are found in and copied out of the ontology's storage, which
+    /// other [`GeneSetMut`](crate::GeneSetMut) types do not expose.
+    pub fn draw_component_samples_with<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+        parallel: bool,
+        mut on_draw: impl FnMut(&Draw),
+    ) -> PosteriorSummary {
+        let (mut components, unannotated): (Vec<Component>, Vec<Component>) = self.gene_ontology
+            .connected_components()
+            .into_iter()
+            .partition(|component| !component.terms.is_empty());
+        if !unannotated.is_empty() {
+            components.push(Component {
+                genes: unannotated.into_iter().flat_map(|component| component.genes).collect(),
+                terms: vec![],
+            });
+        }
+
+        let local_genes = self.gene_ontology.local_gene_indices(&components);
+        let jobs: Vec<(ComponentData<Td, Gd>, ChainRng)> = components.iter()
+            .map(|component| (
+                self.gene_ontology.component_data(component, &local_genes),
+                ChainRng::seed_from_u64(rng.next_u64()),
+            ))
+            .collect();
+        let settings = ChainSettings {
+            burn_in: self.burn_in,
+            nsamples: self.nsamples,
+            thinning: self.thinning,
+            set_activity_probability: self.set_activity_probability,
+            true_active_gene_hit_rate: self.true_active_gene_hit_rate,
+            false_inactive_gene_hit_rate: self.false_inactive_gene_hit_rate,
+            illegal_set_penalty: self.illegal_set_penalty,
+        };
+
+        let runs: Vec<ComponentRun<Td, Gd>> = if parallel && jobs.len() > 1 {
+            let nthreads = thread::available_parallelism().map_or(1, |nthreads| nthreads.get()).min(jobs.len());
+            let queue = Mutex::new(jobs.into_iter().enumerate());
+            let finished = Mutex::new(Vec::new());
+            thread::scope(|scope| {
+                (0..nthreads).for_each(|_| {
+                    scope.spawn(|| loop {
+                        let Some((idx, (data, rng))) = queue.lock().unwrap().next() else { break };
+                        let run = settings.run(data, rng);
+                        finished.lock().unwrap().push((idx, run));
+                    });
+                });
+            });
+            let mut finished = finished.into_inner().unwrap();
+            finished.sort_by_key(|(idx, _)| *idx);
+            finished.into_iter().map(|(_, run)| run).collect()
+        } else {
+            jobs.into_iter().map(|(data, rng)| settings.run(data, rng)).collect()
+        };
+
+        let hyperparameters = Rolemodel::hyperparameters(self);
+        let ndraws = runs.first().map_or(0, |run| run.draws.len());
+        (0..ndraws).for_each(|draw_idx| {
+            let mut active_terms: Vec<usize> = components.iter()
+                .zip(runs.iter())
+                .flat_map(|(component, run)| run.draws[draw_idx].2.iter().map(|&local_idx| component.terms[local_idx]))
+                .collect();
+            active_terms.sort_unstable();
+            on_draw(&Draw {
+                iteration: runs[0].draws[draw_idx].0,
+                log_posterior: runs.iter().map(|run| run.draws[draw_idx].1).sum(),
+                hyperparameters: hyperparameters.clone(),
+                active_terms,
+            });
+        });
+
+        let log_posterior = runs.iter().map(|run| run.log_posterior).sum();
+        self.posterior_llikelihood = SaveableData::new(log_posterior, log_posterior);
+        let arena = &mut self.gene_ontology.arena;
+        components.iter().zip(runs.iter()).for_each(|(component, run)| {
+            component.genes.iter()
+                .zip(run.gene_data.iter())
+                .for_each(|(&idx, data)| arena.gene_data[idx] = *data);
+            component.terms.iter()
+                .zip(run.term_data.iter())
+                .for_each(|(&idx, data)| arena.term_data[idx] = data.clone());
+        });
+
+        PosteriorSummary::from_components(
+            self.gene_ontology.n_wholes(),
+            self.gene_ontology.n_parts(),
+            components.iter().zip(runs.iter().map(|run| &run.summary)),
+        )
+    }
+}
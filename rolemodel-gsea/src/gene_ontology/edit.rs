@@ -0,0 +1,147 @@
+//! Adding and removing genes, terms and annotations.
+//!
+//! Every change updates both directions of the adjacency, so the terms of a
+//! gene and the genes of a term always agree. Removing a node shifts the
+//! indices of the nodes after it down by one, keeping their order.
+
+use crate::gene_ontology::{arena::cycle_term, GeneOntology};
+
+/// Structural changes take the ontology by `&mut`, so no handle to its genes
+/// or terms can be alive to see them.
+impl<Td, Gd> GeneOntology<Td, Gd> {
+    /// Add an unannotated gene called `name`, returning its index. Panics if
+    /// the name is taken.
+    pub fn add_gene(&mut self, name: impl Into<String>, data: Gd) -> usize {
+        self.gene_names.push(name);
+        self.arena.add_gene(data)
+    }
+    /// Add a term without genes or namespace called `name`, returning its
+    /// index. Panics if the name is taken.
+    pub fn add_term(&mut self, name: impl Into<String>, data: Td) -> usize {
+        self.term_names.push(name);
+        self.term_namespaces.push(None);
+        self.arena.add_term(data)
+    }
+
+    /// Annotate gene `gene_idx` to term `term_idx`, returning whether the
+    /// annotation is new. Panics if either index is out of range.
+    pub fn add_annotation(&mut self, term_idx: usize, gene_idx: usize) -> bool {
+        let arena = &mut self.arena;
+        if arena.term_genes.get(term_idx).is_some_and(|genes| genes.contains(&gene_idx)) {
+            return false
+        }
+        arena.annotate(term_idx, gene_idx);
+        true
+    }
+    /// Remove the annotation of gene `gene_idx` to term `term_idx`, returning
+    /// whether there was one. Panics if either index is out of range.
+    pub fn remove_annotation(&mut self, term_idx: usize, gene_idx: usize) -> bool {
+        self.arena.remove_annotation(term_idx, gene_idx)
+    }
+
+    /// Remove gene `idx`, its name and its annotations, returning its data.
+    pub fn remove_gene(&mut self, idx: usize) -> Gd {
+        let data = self.arena.remove_gene(idx);
+        self.gene_names.remove(idx);
+        data
+    }
+    /// Make term `parent_idx` a parent of term `child_idx` in the term hierarchy,
+    /// returning whether the relation is new. Panics if either index is out of
+    /// range or they are the same term.
+    pub fn add_term_relation(&mut self, child_idx: usize, parent_idx: usize) -> bool {
+        self.arena.relate(child_idx, parent_idx)
+    }
+    /// Remove term `parent_idx` from the parents of term `child_idx`, returning
+    /// whether it was one. Panics if either index is out of range.
+    pub fn remove_term_relation(&mut self, child_idx: usize, parent_idx: usize) -> bool {
+        self.arena.unrelate(child_idx, parent_idx)
+    }
+
+    /// Remove term `idx`, its name, namespace and annotations, returning its
+    /// data. Its children become children of its parents.
+    pub fn remove_term(&mut self, idx: usize) -> Td {
+        let data = self.arena.remove_term(idx);
+        self.term_names.remove(idx);
+        self.term_namespaces.remove(idx);
+        data
+    }
+
+    /// Check that names, namespaces and both directions of the adjacency and
+    /// of the term hierarchy agree with each other and with the node data, and
+    /// that the term hierarchy has no cycle.
+    ///
+    /// Panics with a description of the first problem found.
+    pub fn validate(&self) {
+        let arena = &self.arena;
+        let (ngenes, nterms) = (arena.gene_data.len(), arena.term_data.len());
+        assert_eq!(self.gene_names.len(), ngenes, "Ontology has {} gene names for {} genes", self.gene_names.len(), ngenes);
+        assert_eq!(self.term_names.len(), nterms, "Ontology has {} term names for {} terms", self.term_names.len(), nterms);
+        assert_eq!(
+            self.term_namespaces.len(), nterms,
+            "Ontology has {} term namespaces for {} terms", self.term_namespaces.len(), nterms,
+        );
+        assert_eq!(arena.gene_terms.len(), ngenes, "Ontology has {} gene term lists for {} genes", arena.gene_terms.len(), ngenes);
+        assert_eq!(arena.term_genes.len(), nterms, "Ontology has {} term gene lists for {} terms", arena.term_genes.len(), nterms);
+        assert!(
+            arena.term_parents.len() == nterms && arena.term_children.len() == nterms,
+            "Ontology has {} term parent and {} term child lists for {} terms",
+            arena.term_parents.len(), arena.term_children.len(), nterms,
+        );
+
+        let mut from_terms: Vec<(usize, usize)> = arena.term_genes.iter()
+            .enumerate()
+            .flat_map(|(term_idx, genes)| genes.iter().map(move |&gene_idx| (term_idx, gene_idx)))
+            .collect();
+        let mut from_genes: Vec<(usize, usize)> = arena.gene_terms.iter()
+            .enumerate()
+            .flat_map(|(gene_idx, terms)| terms.iter().map(move |&term_idx| (term_idx, gene_idx)))
+            .collect();
+        if let Some((term_idx, gene_idx)) = from_terms.iter().find(|(_, gene_idx)| *gene_idx >= ngenes) {
+            panic!("Term {} lists gene {}, out of range for {} genes", term_idx, gene_idx, ngenes)
+        }
+        if let Some((term_idx, gene_idx)) = from_genes.iter().find(|(term_idx, _)| *term_idx >= nterms) {
+            panic!("Gene {} lists term {}, out of range for {} terms", gene_idx, term_idx, nterms)
+        }
+
+        from_terms.sort_unstable();
+        from_genes.sort_unstable();
+        let mismatch = (0..from_terms.len().max(from_genes.len()))
+            .find_map(|position| match (from_terms.get(position), from_genes.get(position)) {
+                (Some(a), Some(b)) if a == b => None,
+                (Some(a), Some(b)) => Some(*a.min(b)),
+                (a, b) => a.or(b).copied(),
+            });
+        if let Some((term_idx, gene_idx)) = mismatch {
+            panic!("Annotation of term {} to gene {} is not recorded in both directions", term_idx, gene_idx)
+        }
+
+        let mut from_children: Vec<(usize, usize)> = arena.term_parents.iter()
+            .enumerate()
+            .flat_map(|(child_idx, parents)| parents.iter().map(move |&parent_idx| (child_idx, parent_idx)))
+            .collect();
+        let mut from_parents: Vec<(usize, usize)> = arena.term_children.iter()
+            .enumerate()
+            .flat_map(|(parent_idx, children)| children.iter().map(move |&child_idx| (child_idx, parent_idx)))
+            .collect();
+        if let Some((child_idx, parent_idx)) = from_children.iter()
+            .chain(from_parents.iter())
+            .find(|(child_idx, parent_idx)| *child_idx >= nterms || *parent_idx >= nterms || child_idx == parent_idx)
+        {
+            panic!("Term {} cannot have term {} as a parent in an ontology of {} terms", child_idx, parent_idx, nterms)
+        }
+        from_children.sort_unstable();
+        from_parents.sort_unstable();
+        if from_children != from_parents {
+            let (child_idx, parent_idx) = from_children.iter()
+                .zip(from_parents.iter())
+                .find(|(a, b)| a != b)
+                .map(|(a, b)| *a.min(b))
+                .unwrap_or_else(|| *from_children.last().max(from_parents.last()).unwrap());
+            panic!("Term {} as a parent of term {} is not recorded in both directions", parent_idx, child_idx)
+        }
+
+        if let Some(term_idx) = cycle_term(&arena.term_parents) {
+            panic!("Term hierarchy has a cycle through term {}", self.term_names.name(term_idx).unwrap_or_default())
+        }
+    }
+}
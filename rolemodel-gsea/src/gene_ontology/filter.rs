@@ -0,0 +1,113 @@
+//! Dropping terms that are too small, too large or outside the GO namespaces of interest.
+//!
+//! Very small terms add noise and very large ones add run time while saying
+//! little, so both are usually removed before sampling.
+
+use crate::gene_ontology::{namespace::Namespace, GeneOntology};
+
+/// Which terms [`GeneOntology::filter_terms`] keeps.
+///
+/// Sizes count the genes annotated to a term in the ontology being filtered,
+/// so for a loaded ontology they are taken after the gene universe has been
+/// reconciled. Restricting namespaces drops every term whose namespace is unknown.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TermFilter {
+    min_genes: Option<usize>,
+    max_genes: Option<usize>,
+    namespaces: Option<Vec<Namespace>>,
+}
+
+impl TermFilter {
+    /// A filter that keeps every term.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Drop terms with fewer than `min_genes` genes.
+    pub fn min_genes(mut self, min_genes: usize) -> Self {
+        self.min_genes = Some(min_genes);
+        self
+    }
+    /// Drop terms with more than `max_genes` genes.
+    pub fn max_genes(mut self, max_genes: usize) -> Self {
+        self.max_genes = Some(max_genes);
+        self
+    }
+    /// Keep terms in `namespace`. Call again to keep several namespaces.
+    pub fn namespace(mut self, namespace: Namespace) -> Self {
+        self.namespaces.get_or_insert_with(Vec::new).push(namespace);
+        self
+    }
+
+    fn validate(&self) {
+        if let (Some(min_genes), Some(max_genes)) = (self.min_genes, self.max_genes) {
+            assert!(
+                min_genes <= max_genes,
+                "Term filter keeps no term: min_genes {} exceeds max_genes {}", min_genes, max_genes,
+            );
+        }
+    }
+}
+
+/// What [`GeneOntology::filter_terms`] removed. Every removed term is listed
+/// once, under the first check it failed, in the order namespace, size.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TermFilterReport {
+    /// Terms before filtering.
+    pub total_terms: usize,
+    /// Terms after filtering.
+    pub kept_terms: usize,
+    /// Terms outside the kept namespaces, or without a namespace.
+    pub other_namespace: Vec<String>,
+    /// Terms with fewer than the minimum number of genes.
+    pub too_small: Vec<String>,
+    /// Terms with more than the maximum number of genes.
+    pub too_large: Vec<String>,
+    /// Genes that lost all of their terms. They stay in the ontology without annotations.
+    pub unannotated_genes: Vec<String>,
+}
+
+impl<Td, Gd> GeneOntology<Td, Gd> {
+    /// Rebuild the ontology with the terms `filter` keeps and every gene.
+    /// Removed terms disappear from the term lists of their genes.
+    pub fn filter_terms(self, filter: &TermFilter) -> (Self, TermFilterReport) {
+        filter.validate();
+
+        let mut report = TermFilterReport {
+            total_terms: self.arena.term_data.len(),
+            ..Default::default()
+        };
+        let term_name = |idx: usize| self.term_names.name(idx).unwrap_or_default().to_string();
+
+        let kept: Vec<usize> = (0..self.arena.term_data.len())
+            .filter(|&idx| {
+                let size = self.arena.term_genes[idx].len();
+                let in_namespace = match &filter.namespaces {
+                    Some(namespaces) => self.term_namespace(idx)
+                        .is_some_and(|namespace| namespaces.contains(&namespace)),
+                    None => true,
+                };
+                if !in_namespace {
+                    report.other_namespace.push(term_name(idx));
+                } else if filter.min_genes.is_some_and(|min_genes| size < min_genes) {
+                    report.too_small.push(term_name(idx));
+                } else if filter.max_genes.is_some_and(|max_genes| size > max_genes) {
+                    report.too_large.push(term_name(idx));
+                } else {
+                    return true
+                }
+                false
+            })
+            .collect();
+        report.kept_terms = kept.len();
+
+        let annotated: Vec<bool> = self.arena.gene_terms.iter().map(|terms| !terms.is_empty()).collect();
+        let filtered = self.retain_terms(&kept);
+        report.unannotated_genes = annotated.iter()
+            .enumerate()
+            .filter(|&(idx, &annotated)| annotated && filtered.arena.gene_terms[idx].is_empty())
+            .map(|(idx, _)| filtered.gene_names.name(idx).unwrap_or_default().to_string())
+            .collect();
+
+        (filtered, report)
+    }
+}
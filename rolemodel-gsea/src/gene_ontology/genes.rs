@@ -1,109 +1,89 @@
 use crate::{
-    gene_ontology::terms::Term, 
-    rolemodel::saveable::Saveable, 
-    Activeable, 
-    Node, Part
+    gene_ontology::{arena::Arena, terms::Term},
+    Node, NodeMut, Part,
 };
 
-use std::{
-    cell::{
-        Ref, RefCell,
-    },
-    rc::Rc,
-};
-
-#[derive(Debug)]
-pub struct Gene<Td, Gd> {
-    data: Gd,
-    terms: Vec<Rc<RefCell<Term<Td, Gd>>>>,
+/// Handle to one gene of a [`GeneOntology`](crate::GeneOntology).
+///
+/// Handles are cheap to copy and borrow the ontology's storage, so the
+/// ontology cannot change while one is alive.
+pub struct Gene<'a, Td, Gd> {
+    arena: &'a Arena<Td, Gd>,
+    idx: usize,
 }
 
-impl<Td, Gd> Gene<Td, Gd> {
-    pub fn new(data: Gd, terms: Vec<Rc<RefCell<Term<Td, Gd>>>>) -> Self {
+impl<'a, Td, Gd> Gene<'a, Td, Gd> {
+    pub(crate) fn new(arena: &'a Arena<Td, Gd>, idx: usize) -> Self {
         Self {
-            data,
-            terms,
+            arena,
+            idx,
         }
     }
-    pub fn terms(&self) -> &Vec<Rc<RefCell<Term<Td, Gd>>>> {&self.terms}
-    pub fn add_term(&mut self, term: Rc<RefCell<Term<Td, Gd>>>) {self.terms.push(term)} 
+    /// Position of the gene in its ontology.
+    pub fn index(&self) -> usize {self.idx}
 }
 
-impl<Td, Gd> Node for Gene<Td, Gd> {
-    type Data = Gd;
-    type NeighborType = Rc<RefCell<Term<Td, Gd>>>;
-
-    fn data(&self) -> &Self::Data {
-        &self.data
-    }
-    fn data_mut(&mut self) -> &mut Self::Data {
-        &mut self.data
+impl<Td, Gd> Clone for Gene<'_, Td, Gd> {
+    fn clone(&self) -> Self {
+        *self
     }
-    fn ref_data(&self) -> Ref<Self::Data> {
-        panic!("Never call ref_data on items not in a RefCell");
+}
+impl<Td, Gd> Copy for Gene<'_, Td, Gd> { }
+
+impl<Td, Gd> PartialEq for Gene<'_, Td, Gd> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.arena, other.arena) && self.idx == other.idx
     }
-    fn iter_neighbors(&self) -> impl Iterator<Item = Self::NeighborType> {
-        self.terms.clone().into_iter()
+}
+
+impl<Td, Gd: std::fmt::Debug> std::fmt::Debug for Gene<'_, Td, Gd> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Gene")
+            .field("index", &self.idx)
+            .field("data", self.data())
+            .finish()
     }
 }
-impl<Td, Gd> Node for Rc<RefCell<Gene<Td, Gd>>> {
+
+impl<'a, Td, Gd> Node for Gene<'a, Td, Gd> {
     type Data = Gd;
-    type NeighborType = Rc<RefCell<Term<Td, Gd>>>;
+    type NeighborType = Term<'a, Td, Gd>;
 
-    fn data(&self) -> &Self::Data {
-        panic!("Cannot borrow data from inside a RefCell - use ref_data instead")
-    }
-    fn data_mut(&mut self) -> &mut Self::Data {
-        panic!("Cannot borrow data from inside a RefCell - use ref_data_mut instead")
-    }
-    fn ref_data(&self) -> Ref<Self::Data> {
-        Ref::map(self.borrow(), |whole| &whole.data)
+    fn data(&self) -> &Gd {
+        &self.arena.gene_data[self.idx]
     }
     fn iter_neighbors(&self) -> impl Iterator<Item = Self::NeighborType> {
-        self.borrow().terms.clone().into_iter()
+        let arena = self.arena;
+        arena.gene_terms[self.idx].iter().map(move |&idx| Term::new(arena, idx))
     }
 }
 
-impl<Td, Gd> Part for Gene<Td, Gd> { }
-impl<Td, Gd> Part for Rc<RefCell<Gene<Td, Gd>>> { }
+impl<Td, Gd> Part for Gene<'_, Td, Gd> { }
 
-impl<Td, Gd> Saveable for Gene<Td, Gd> 
-where 
-    Td: Saveable,
-    Gd: Saveable,
-{
-    type Output = Gd::Output;
+/// Exclusive handle to the data of one gene, from [`GeneSetMut::part_mut`](crate::GeneSetMut::part_mut).
+pub struct GeneMut<'a, Gd> {
+    data: &'a mut Gd,
+    idx: usize,
+}
 
-    fn save(&mut self) {
-        self.data.save();
-    }
-    fn restore(&mut self) {
-        self.data.restore();
-    }
-    fn current(&self) -> Self::Output {
-        self.data.current()
-    }
-    fn saved(&self) -> Self::Output {
-        self.data.saved()
+impl<'a, Gd> GeneMut<'a, Gd> {
+    pub(crate) fn new(data: &'a mut Gd, idx: usize) -> Self {
+        Self {
+            data,
+            idx,
+        }
     }
+    /// Position of the gene in its ontology.
+    pub fn index(&self) -> usize {self.idx}
 }
 
-impl<Td, Gd> Saveable for Rc<RefCell<Gene<Td, Gd>>> 
-where 
-    Gd: Saveable,
-{
-    type Output = Gd::Output;
+impl<Gd> NodeMut for GeneMut<'_, Gd> {
+    type Data = Gd;
 
-    fn save(&mut self) {
-        self.borrow_mut().data.save();
+    fn data(&self) -> &Gd {
+        self.data
     }
-    fn restore(&mut self) {
-        self.borrow_mut().data.restore();
+    fn data_mut(&mut self) -> &mut Gd {
+        self.data
     }
-    fn current(&self) -> Self::Output {
-        self.data().current()
-    }
-    fn saved(&self) -> Self::Output {
-        self.data().saved()
-    }
-}
\ No newline at end of file
+}
@@ -0,0 +1,389 @@
+//! Gene ontology with owned node data and no term hierarchy.
+//!
+//! [`GeneOntGraph`] stores genes and terms in two vectors and links them by
+//! index. Node data is reached through plain references, so nothing is
+//! reference counted or borrow checked at run time. Every neighbour list is
+//! kept sorted and free of repeats, which is what lets
+//! [`GeneOntGraph::iter_term_neighbors_mut`] and
+//! [`GeneOntGraph::iter_gene_neighbors_mut`] hand out several `&mut` at once.
+
+use crate::{
+    gene_ontology::{names::NameIndex, namespace::Namespace, GeneOntology},
+    rolemodel::saveable::Saveable,
+    GeneSet, GeneSetMut, Node, NodeMut, Part, Whole,
+};
+
+use std::slice;
+
+/// Sorted node indices without repeats.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct IdxVec {
+    vec: Vec<usize>,
+}
+impl IdxVec {
+    /// Returns whether `idx` was not already present.
+    fn insert(&mut self, idx: usize) -> bool {
+        match self.vec.binary_search(&idx) {
+            Ok(_) => false,
+            Err(position) => {
+                self.vec.insert(position, idx);
+                true
+            }
+        }
+    }
+    fn as_slice(&self) -> &[usize] {
+        &self.vec
+    }
+}
+
+/// Mutable references to the nodes of `target` at the positions in an [`IdxVec`].
+///
+/// The indices are strictly increasing, so each node is split off the front of
+/// the part of `target` not yet handed out and no node is returned twice.
+struct IdxVecIterMut<'a, D> {
+    indices: slice::Iter<'a, usize>,
+    rest: &'a mut [D],
+    offset: usize,
+}
+impl<'a, D> Iterator for IdxVecIterMut<'a, D> {
+    type Item = &'a mut D;
+
+    fn next(&mut self) -> Option<&'a mut D> {
+        let next_idx = *self.indices.next()?;
+        let len = self.offset + self.rest.len();
+        let rest = std::mem::take(&mut self.rest);
+        let (node, rest) = rest[next_idx - self.offset..]
+            .split_first_mut()
+            .unwrap_or_else(|| panic!("Attempted to access vector of length {} at index {}", len, next_idx));
+        self.rest = rest;
+        self.offset = next_idx + 1;
+        Some(node)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl<'a, D> IdxVecIterMut<'a, D> {
+    fn new(vec: &'a IdxVec, target: &'a mut [D]) -> Self {
+        Self {
+            indices: vec.as_slice().iter(),
+            rest: target,
+            offset: 0,
+        }
+    }
+}
+
+/// Data of one gene or term of a [`GeneOntGraph`] and the indices of its neighbours.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphNode<D> {
+    data: D,
+    neighbors: IdxVec,
+}
+
+impl<D> GraphNode<D> {
+    fn new(data: D) -> Self {
+        Self {
+            data,
+            neighbors: IdxVec::default(),
+        }
+    }
+    pub fn data(&self) -> &D {
+        &self.data
+    }
+    pub fn data_mut(&mut self) -> &mut D {
+        &mut self.data
+    }
+    /// Indices of the neighbouring terms of a gene, or genes of a term, in increasing order.
+    pub fn neighbors(&self) -> &[usize] {
+        self.neighbors.as_slice()
+    }
+}
+
+impl<D> NodeMut for GraphNode<D> {
+    type Data = D;
+
+    fn data(&self) -> &D {
+        &self.data
+    }
+    fn data_mut(&mut self) -> &mut D {
+        &mut self.data
+    }
+}
+
+/// Bipartite graph of genes and terms holding its node data by value.
+///
+/// Annotating a gene to a term it already belongs to changes nothing, so
+/// neither side ever lists the same neighbour twice.
+#[derive(Clone, Debug)]
+pub struct GeneOntGraph<Td, Gd> {
+    terms: Vec<GraphNode<Td>>,
+    genes: Vec<GraphNode<Gd>>,
+    gene_names: NameIndex,
+    term_names: NameIndex,
+    term_namespaces: Vec<Option<Namespace>>,
+}
+
+impl<Td, Gd> GeneOntGraph<Td, Gd> {
+    /// Unannotated genes and terms, named by their index.
+    pub fn new(gene_data: Vec<Gd>, term_data: Vec<Td>) -> Self {
+        Self::from_incidence(gene_data, term_data, vec![])
+    }
+
+    /// Genes and terms are named by their index.
+    pub fn from_incidence(gene_data: Vec<Gd>, term_data: Vec<Td>, adj: Vec<(usize, usize)>) -> Self {
+        let gene_names = NameIndex::numbered(gene_data.len());
+        let term_names = NameIndex::numbered(term_data.len());
+        Self::from_named_incidence(gene_names, gene_data, term_names, term_data, adj)
+    }
+
+    /// Build the graph from node data aligned with `gene_names` and `term_names`,
+    /// and (term index, gene index) annotations. Repeated annotations are kept once.
+    pub fn from_named_incidence(
+        gene_names: NameIndex, gene_data: Vec<Gd>,
+        term_names: NameIndex, term_data: Vec<Td>,
+        adj: Vec<(usize, usize)>,
+    ) -> Self {
+        assert_eq!(gene_names.len(), gene_data.len(), "Every gene must have exactly one name");
+        assert_eq!(term_names.len(), term_data.len(), "Every term must have exactly one name");
+
+        let mut graph = Self {
+            terms: term_data.into_iter().map(GraphNode::new).collect(),
+            genes: gene_data.into_iter().map(GraphNode::new).collect(),
+            gene_names,
+            term_namespaces: vec![None; term_names.len()],
+            term_names,
+        };
+        adj.iter().for_each(|(term_idx, gene_idx)| { graph.annotate(*term_idx, *gene_idx); });
+        graph
+    }
+
+    /// Annotate gene `gene_idx` to term `term_idx`. Returns whether the
+    /// annotation is new. Panics if either index is out of range.
+    pub fn annotate(&mut self, term_idx: usize, gene_idx: usize) -> bool {
+        assert!(term_idx < self.terms.len(), "Term {} out of range for {} terms", term_idx, self.terms.len());
+        assert!(gene_idx < self.genes.len(), "Gene {} out of range for {} genes", gene_idx, self.genes.len());
+        self.genes[gene_idx].neighbors.insert(term_idx);
+        self.terms[term_idx].neighbors.insert(gene_idx)
+    }
+
+    pub fn n_genes(&self) -> usize {
+        self.genes.len()
+    }
+    pub fn n_terms(&self) -> usize {
+        self.terms.len()
+    }
+    pub fn gene_names(&self) -> &NameIndex {
+        &self.gene_names
+    }
+    pub fn term_names(&self) -> &NameIndex {
+        &self.term_names
+    }
+    pub fn term_namespace(&self, idx: usize) -> Option<Namespace> {
+        *self.term_namespaces.get(idx)?
+    }
+    pub fn set_term_namespace(&mut self, idx: usize, namespace: Namespace) {
+        self.term_namespaces[idx] = Some(namespace);
+    }
+
+    pub fn gene(&self, idx: usize) -> Option<&GraphNode<Gd>> {
+        self.genes.get(idx)
+    }
+    pub fn gene_mut(&mut self, idx: usize) -> Option<&mut GraphNode<Gd>> {
+        self.genes.get_mut(idx)
+    }
+    pub fn term(&self, idx: usize) -> Option<&GraphNode<Td>> {
+        self.terms.get(idx)
+    }
+    pub fn term_mut(&mut self, idx: usize) -> Option<&mut GraphNode<Td>> {
+        self.terms.get_mut(idx)
+    }
+
+    pub fn iter_terms(&self) -> impl Iterator<Item = &GraphNode<Td>> {
+        self.terms.iter()
+    }
+    pub fn iter_terms_mut(&mut self) -> impl Iterator<Item = &mut GraphNode<Td>> {
+        self.terms.iter_mut()
+    }
+    /// Genes annotated to term `term_idx`. Panics if it is out of range.
+    pub fn iter_term_neighbors(&self, term_idx: usize) -> impl Iterator<Item = &GraphNode<Gd>> {
+        self.terms[term_idx].neighbors().iter().map(|&gene_idx| &self.genes[gene_idx])
+    }
+    pub fn iter_term_neighbors_mut(&mut self, term_idx: usize) -> impl Iterator<Item = &mut GraphNode<Gd>> {
+        IdxVecIterMut::new(&self.terms[term_idx].neighbors, &mut self.genes)
+    }
+
+    pub fn iter_genes(&self) -> impl Iterator<Item = &GraphNode<Gd>> {
+        self.genes.iter()
+    }
+    pub fn iter_genes_mut(&mut self) -> impl Iterator<Item = &mut GraphNode<Gd>> {
+        self.genes.iter_mut()
+    }
+    /// Terms annotating gene `gene_idx`. Panics if it is out of range.
+    pub fn iter_gene_neighbors(&self, gene_idx: usize) -> impl Iterator<Item = &GraphNode<Td>> {
+        self.genes[gene_idx].neighbors().iter().map(|&term_idx| &self.terms[term_idx])
+    }
+    pub fn iter_gene_neighbors_mut(&mut self, gene_idx: usize) -> impl Iterator<Item = &mut GraphNode<Td>> {
+        IdxVecIterMut::new(&self.genes[gene_idx].neighbors, &mut self.terms)
+    }
+}
+
+/// Copies the node data out of the ontology's storage. The graph has
+/// no term hierarchy, so parent and child relations between terms are left behind.
+impl<Td: Clone, Gd: Clone> From<&GeneOntology<Td, Gd>> for GeneOntGraph<Td, Gd> {
+    fn from(gene_ontology: &GeneOntology<Td, Gd>) -> Self {
+        let arena = &gene_ontology.arena;
+        let adj = arena.term_genes.iter()
+            .enumerate()
+            .flat_map(|(term_idx, genes)| genes.iter().map(move |gene_idx| (term_idx, *gene_idx)))
+            .collect();
+        let mut graph = Self::from_named_incidence(
+            gene_ontology.gene_names().clone(),
+            arena.gene_data.clone(),
+            gene_ontology.term_names().clone(),
+            arena.term_data.clone(),
+            adj,
+        );
+        graph.term_namespaces = gene_ontology.term_namespaces.clone();
+        graph
+    }
+}
+
+impl<Td, Gd> From<GeneOntGraph<Td, Gd>> for GeneOntology<Td, Gd> {
+    fn from(graph: GeneOntGraph<Td, Gd>) -> Self {
+        let adj = graph.terms.iter()
+            .enumerate()
+            .flat_map(|(term_idx, term)| term.neighbors().iter().map(move |gene_idx| (term_idx, *gene_idx)))
+            .collect();
+        let mut gene_ontology = GeneOntology::from_named_incidence(
+            graph.gene_names,
+            graph.genes.into_iter().map(|gene| gene.data).collect(),
+            graph.term_names,
+            graph.terms.into_iter().map(|term| term.data).collect(),
+            adj,
+        );
+        gene_ontology.term_namespaces = graph.term_namespaces;
+        gene_ontology
+    }
+}
+
+/// Read-only view of one gene of a [`GeneOntGraph`], as handed out by its [`GeneSet`] impl.
+///
+/// Views implement [`Node`] but not [`NodeMut`]; change node data through
+/// [`GeneSetMut`] or [`GeneOntGraph::gene_mut`] and friends, whose
+/// [`GraphNode`]s are `NodeMut`.
+pub struct GraphGene<'a, Td, Gd> {
+    graph: &'a GeneOntGraph<Td, Gd>,
+    idx: usize,
+}
+
+/// Read-only view of one term of a [`GeneOntGraph`], as handed out by its [`GeneSet`] impl.
+pub struct GraphTerm<'a, Td, Gd> {
+    graph: &'a GeneOntGraph<Td, Gd>,
+    idx: usize,
+}
+
+impl<Td, Gd> GraphGene<'_, Td, Gd> {
+    /// Position of the gene in its graph.
+    pub fn index(&self) -> usize {
+        self.idx
+    }
+}
+impl<Td, Gd> GraphTerm<'_, Td, Gd> {
+    /// Position of the term in its graph.
+    pub fn index(&self) -> usize {
+        self.idx
+    }
+}
+
+impl<Td, Gd> Clone for GraphGene<'_, Td, Gd> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<Td, Gd> Copy for GraphGene<'_, Td, Gd> { }
+impl<Td, Gd> Clone for GraphTerm<'_, Td, Gd> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<Td, Gd> Copy for GraphTerm<'_, Td, Gd> { }
+
+impl<'a, Td, Gd> Node for GraphGene<'a, Td, Gd> {
+    type Data = Gd;
+    type NeighborType = GraphTerm<'a, Td, Gd>;
+
+    fn data(&self) -> &Gd {
+        &self.graph.genes[self.idx].data
+    }
+    fn iter_neighbors(&self) -> impl Iterator<Item = Self::NeighborType> {
+        let graph = self.graph;
+        graph.genes[self.idx].neighbors().iter().map(move |&idx| GraphTerm { graph, idx })
+    }
+}
+
+impl<'a, Td, Gd> Node for GraphTerm<'a, Td, Gd> {
+    type Data = Td;
+    type NeighborType = GraphGene<'a, Td, Gd>;
+
+    fn data(&self) -> &Td {
+        &self.graph.terms[self.idx].data
+    }
+    fn iter_neighbors(&self) -> impl Iterator<Item = Self::NeighborType> {
+        let graph = self.graph;
+        graph.terms[self.idx].neighbors().iter().map(move |&idx| GraphGene { graph, idx })
+    }
+}
+
+impl<Td, Gd> Part for GraphGene<'_, Td, Gd> { }
+impl<Td, Gd> Whole for GraphTerm<'_, Td, Gd> { }
+
+impl<Td, Gd> GeneSet for GeneOntGraph<Td, Gd> {
+    type PartData = Gd;
+    type WholeData = Td;
+    type PartNode<'a> = GraphGene<'a, Td, Gd> where Self: 'a;
+    type WholeNode<'a> = GraphTerm<'a, Td, Gd> where Self: 'a;
+
+    fn iter_parts(&self) -> impl Iterator<Item = Self::PartNode<'_>> {
+        (0..self.genes.len()).map(move |idx| GraphGene { graph: self, idx })
+    }
+    fn iter_wholes(&self) -> impl Iterator<Item = Self::WholeNode<'_>> {
+        (0..self.terms.len()).map(move |idx| GraphTerm { graph: self, idx })
+    }
+
+    fn n_parts(&self) -> usize { self.genes.len() }
+    fn n_wholes(&self) -> usize { self.terms.len() }
+    fn part(&self, idx: usize) -> Option<Self::PartNode<'_>> {
+        (idx < self.genes.len()).then_some(GraphGene { graph: self, idx })
+    }
+    fn whole(&self, idx: usize) -> Option<Self::WholeNode<'_>> {
+        (idx < self.terms.len()).then_some(GraphTerm { graph: self, idx })
+    }
+}
+impl<Td, Gd> GeneSetMut for GeneOntGraph<Td, Gd> {
+    type PartNodeMut<'a> = &'a mut GraphNode<Gd> where Self: 'a;
+    type WholeNodeMut<'a> = &'a mut GraphNode<Td> where Self: 'a;
+
+    fn part_mut(&mut self, idx: usize) -> Option<Self::PartNodeMut<'_>> {
+        self.gene_mut(idx)
+    }
+    fn whole_mut(&mut self, idx: usize) -> Option<Self::WholeNodeMut<'_>> {
+        self.term_mut(idx)
+    }
+}
+
+impl<Td: Saveable, Gd: Saveable> Saveable for GeneOntGraph<Td, Gd> {
+    type Output = ();
+
+    fn current(&self) -> Self::Output { }
+    fn saved(&self) -> Self::Output { }
+    fn restore(&mut self) {
+        self.genes.iter_mut().for_each(|gene| gene.data.restore());
+        self.terms.iter_mut().for_each(|term| term.data.restore());
+    }
+    fn save(&mut self) {
+        self.genes.iter_mut().for_each(|gene| gene.data.save());
+        self.terms.iter_mut().for_each(|term| term.data.save());
+    }
+}
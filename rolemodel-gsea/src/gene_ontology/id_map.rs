@@ -0,0 +1,95 @@
+use crate::gene_ontology::loader::{Table, TableSource};
+
+use hashbrown::HashMap;
+
+/// Outcome of looking up an identifier in a [`GeneIdMap`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GeneIdResolution {
+    /// The identifier belongs to exactly one gene.
+    Mapped(String),
+    /// The identifier is not in the map.
+    Unmapped,
+    /// The identifier belongs to several genes, listed in sorted order.
+    Ambiguous(Vec<String>),
+}
+
+/// Mapping from any gene identifier (symbol, Ensembl ID, Entrez ID or alias) to a canonical ID.
+///
+/// Lookups ignore ASCII case and surrounding whitespace. Every canonical ID
+/// maps to itself, so tables already using canonical IDs resolve unchanged.
+#[derive(Clone, Debug, Default)]
+pub struct GeneIdMap {
+    ids: HashMap<String, Vec<String>>,
+}
+
+impl GeneIdMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `id` as an identifier of the gene `canonical`.
+    pub fn insert(&mut self, id: &str, canonical: &str) {
+        [id, canonical].into_iter().for_each(|key| {
+            let candidates = self.ids.entry(Self::key(key)).or_default();
+            if !candidates.iter().any(|candidate| candidate == canonical) {
+                candidates.push(canonical.to_string());
+                candidates.sort();
+            }
+        });
+    }
+
+    /// Read a mapping table with one gene per row.
+    ///
+    /// `canonical_column` holds the ID genes are mapped to; every non-empty field
+    /// of the `alias_columns` is recorded as an identifier of that gene.
+    pub fn from_table(
+        source: impl Into<TableSource>,
+        canonical_column: &str,
+        alias_columns: &[&str],
+        delimiter: u8,
+    ) -> Self {
+        let table = Table::open(source.into(), true, delimiter);
+        let canonical_idx = table.column_index(canonical_column, "canonical gene IDs");
+        let alias_idx: Vec<usize> = alias_columns.iter()
+            .map(|column| table.column_index(column, "gene aliases"))
+            .collect();
+        let description = table.description;
+
+        let mut id_map = Self::new();
+        table.records.enumerate().for_each(|(entry_idx, result)| {
+            let record = result
+                .unwrap_or_else(|_| panic!("Could not read entry {} of {}", entry_idx, description));
+            let canonical = record.get(canonical_idx).unwrap_or("").trim();
+            if canonical.is_empty() {
+                return
+            }
+            id_map.insert(canonical, canonical);
+            alias_idx.iter()
+                .filter_map(|&idx| record.get(idx))
+                .map(|alias| alias.trim())
+                .filter(|alias| !alias.is_empty())
+                .for_each(|alias| id_map.insert(alias, canonical));
+        });
+
+        id_map
+    }
+
+    pub fn resolve(&self, id: &str) -> GeneIdResolution {
+        match self.ids.get(&Self::key(id)).map(|candidates| candidates.as_slice()) {
+            None | Some([]) => GeneIdResolution::Unmapped,
+            Some([canonical]) => GeneIdResolution::Mapped(canonical.clone()),
+            Some(candidates) => GeneIdResolution::Ambiguous(candidates.to_vec()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    fn key(id: &str) -> String {
+        id.trim().to_ascii_uppercase()
+    }
+}
@@ -0,0 +1,1178 @@
+use crate::gene_ontology::{
+    filter::{TermFilter, TermFilterReport},
+    id_map::{GeneIdMap, GeneIdResolution},
+    names::NameIndex,
+    namespace::Namespace,
+    arena::bridge_parents,
+    GeneOntology,
+};
+
+use hashbrown::{HashMap, HashSet};
+use itertools::Itertools;
+use rv::data::Booleable;
+
+/// A delimited table read by [`GeneOntologyLoader`].
+///
+/// Paths convert into [`TableSource::File`], so `loader.gene_data("results.csv")`
+/// works as before; streams and tables already in memory are wrapped with
+/// [`TableSource::reader`] and [`TableSource::rows`].
+pub enum TableSource {
+    File(String),
+    Reader(Box<dyn std::io::Read>),
+    /// Rows of fields, including the header row when columns are named.
+    Rows(Vec<Vec<String>>),
+}
+
+impl TableSource {
+    pub fn reader(reader: impl std::io::Read + 'static) -> Self {
+        Self::Reader(Box::new(reader))
+    }
+    pub fn rows<R, S>(rows: impl IntoIterator<Item = R>) -> Self
+    where
+        R: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Rows(
+            rows.into_iter()
+                .map(|row| row.into_iter().map(|field| field.into()).collect())
+                .collect()
+        )
+    }
+}
+
+impl From<&str> for TableSource {
+    fn from(file: &str) -> Self {
+        Self::File(file.to_string())
+    }
+}
+impl From<String> for TableSource {
+    fn from(file: String) -> Self {
+        Self::File(file)
+    }
+}
+
+impl std::fmt::Display for TableSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::File(file) => write!(f, "file {}", file),
+            Self::Reader(_) => write!(f, "reader"),
+            Self::Rows(_) => write!(f, "in-memory rows"),
+        }
+    }
+}
+
+/// What to do with a gene whose data field holds a missing-value marker such as `NA`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissingPolicy {
+    /// Leave the gene, and its annotations, out of the ontology.
+    #[default]
+    Drop,
+    /// Keep the gene with the data given to
+    /// [`GeneOntologyLoader::non_hit_gene_data`], which the model should read
+    /// as a non-hit.
+    NonHit,
+    /// Keep the gene with the data given to
+    /// [`GeneOntologyLoader::unobserved_gene_data`], whose
+    /// `Booleable::try_into_bool` is `None`, so it is marginalised out of the
+    /// part likelihood.
+    Unobserved,
+}
+
+/// Which genes form the universe when the gene data and adjacency tables disagree.
+///
+/// Only applies when a gene data table is given; otherwise the universe is
+/// every annotated gene.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Universe {
+    /// Genes with both data and at least one annotation.
+    #[default]
+    Intersection,
+    /// Every gene in the gene data table; annotations of other genes are dropped.
+    Data,
+    /// Every annotated gene; genes absent from the gene data table get `D::default()` data.
+    Annotation,
+}
+
+/// Summary of the adjustments [`GeneOntologyLoader::load_with_report`] made while reading.
+#[derive(Clone, Debug, Default)]
+pub struct LoadReport {
+    /// Policy that decided `dropped_genes`, `unannotated_genes` and `defaulted_genes`.
+    pub universe: Universe,
+    /// Genes left out of the universe, along with their annotations.
+    pub dropped_genes: Vec<String>,
+    /// Genes kept in the universe without any annotation.
+    pub unannotated_genes: Vec<String>,
+    /// Annotated genes absent from the gene data, kept with default data.
+    pub defaulted_genes: Vec<String>,
+    /// Terms with annotations in the adjacency table that kept none after reconciliation.
+    pub emptied_terms: Vec<String>,
+
+    /// Policy applied to `missing_genes`.
+    pub missing_policy: MissingPolicy,
+    /// Genes whose data field held a missing-value marker.
+    pub missing_genes: Vec<String>,
+    /// Gene identifiers absent from the [`GeneIdMap`], kept unchanged.
+    pub unmapped_genes: Vec<String>,
+    /// Gene identifiers the [`GeneIdMap`] assigns to several genes, kept
+    /// unchanged, with their candidate canonical IDs.
+    pub ambiguous_genes: Vec<(String, Vec<String>)>,
+    /// Genes whose identifier mapped onto a gene already in the gene data.
+    /// Only the first entry was kept.
+    pub merged_genes: Vec<String>,
+
+    /// Terms in the hierarchy table but not in the ontology. Their children
+    /// were linked to their nearest ancestors that are in it.
+    pub bridged_hierarchy_terms: Vec<String>,
+    /// Terms removed by the [`TermFilter`], if one was given.
+    pub term_filter: Option<TermFilterReport>,
+}
+
+impl LoadReport {
+    /// Print a warning for each kind of adjustment that was made.
+    fn print_warnings(&self) {
+        if !self.unmapped_genes.is_empty() || !self.ambiguous_genes.is_empty() {
+            println!(
+                "Warning: {} gene identifiers could not be mapped and {} are ambiguous. Keeping them unchanged.",
+                self.unmapped_genes.len(),
+                self.ambiguous_genes.len(),
+            );
+        }
+        if !self.merged_genes.is_empty() {
+            println!(
+                "Warning: {} genes appear in data multiple times after mapping identifiers. Skipping repeated entries.",
+                self.merged_genes.len(),
+            );
+        }
+        if !self.missing_genes.is_empty() {
+            println!(
+                "Warning: {} genes have missing data. Applying MissingPolicy::{:?}.",
+                self.missing_genes.len(),
+                self.missing_policy,
+            );
+        }
+        if !self.dropped_genes.is_empty() || !self.emptied_terms.is_empty() {
+            println!(
+                "Warning: Universe::{:?} dropped {} genes, and {} terms lost all of their genes.",
+                self.universe,
+                self.dropped_genes.len(),
+                self.emptied_terms.len(),
+            );
+        }
+    }
+}
+
+/// Builder for reading a [`GeneOntology`] from delimited tables.
+///
+/// Every table and column is set through a named method, and the combination is
+/// checked by [`GeneOntologyLoader::load`] before anything is read. Only the
+/// adjacency table is required; gene and term data default to `D::default()`
+/// for every name appearing in the adjacency data when no table is given.
+///
+/// Columns are optional: when neither column of a table is named, the table is
+/// read without a header row and its first two columns are used.
+///
+/// Gene data fields equal to one of [`GeneOntologyLoader::missing_values`] are
+/// handled by the [`MissingPolicy`] set with [`GeneOntologyLoader::missing_genes`].
+pub struct GeneOntologyLoader<Td, Gd, Dt = Td, Dg = Gd> {
+    gene_data: Option<TableSource>,
+    gene_name_column: Option<String>,
+    gene_data_column: Option<String>,
+    gene_whole_row: bool,
+
+    term_data: Option<TableSource>,
+    term_name_column: Option<String>,
+    term_data_column: Option<String>,
+
+    gene_descriptions: Option<TableSource>,
+    gene_description_name_column: Option<String>,
+    gene_description_column: Option<String>,
+
+    term_descriptions: Option<TableSource>,
+    term_description_name_column: Option<String>,
+    term_description_column: Option<String>,
+
+    term_namespaces: Option<TableSource>,
+    term_namespace_name_column: Option<String>,
+    term_namespace_column: Option<String>,
+    term_hierarchy: Option<TableSource>,
+    term_hierarchy_child_column: Option<String>,
+    term_hierarchy_parent_column: Option<String>,
+    term_filter: Option<TermFilter>,
+
+    adjacency: Option<TableSource>,
+    adjacency_term_column: Option<String>,
+    adjacency_gene_column: Option<String>,
+
+    delimiter: u8,
+
+    gene_id_map: Option<GeneIdMap>,
+    universe: Universe,
+
+    missing_values: Vec<String>,
+    missing_policy: MissingPolicy,
+    non_hit_gene_data: Option<Box<dyn Fn() -> Gd>>,
+    unobserved_gene_data: Option<Box<dyn Fn() -> Gd>>,
+
+    gene_transform: Box<dyn Fn(Dg) -> Gd>,
+    term_transform: Box<dyn Fn(Dt) -> Td>,
+}
+
+impl<Td: 'static, Gd: 'static> GeneOntologyLoader<Td, Gd> {
+    pub fn new() -> Self {
+        Self {
+            gene_data: None,
+            gene_name_column: None,
+            gene_data_column: None,
+            gene_whole_row: false,
+            term_data: None,
+            term_name_column: None,
+            term_data_column: None,
+            gene_descriptions: None,
+            gene_description_name_column: None,
+            gene_description_column: None,
+            term_descriptions: None,
+            term_description_name_column: None,
+            term_description_column: None,
+            term_namespaces: None,
+            term_namespace_name_column: None,
+            term_namespace_column: None,
+            term_hierarchy: None,
+            term_hierarchy_child_column: None,
+            term_hierarchy_parent_column: None,
+            term_filter: None,
+            adjacency: None,
+            adjacency_term_column: None,
+            adjacency_gene_column: None,
+            delimiter: b',',
+            gene_id_map: None,
+            universe: Universe::Intersection,
+            missing_values: vec!["".into(), "NA".into(), "NaN".into(), "N/A".into()],
+            missing_policy: MissingPolicy::Drop,
+            non_hit_gene_data: None,
+            unobserved_gene_data: None,
+            gene_transform: Box::new(|x| x),
+            term_transform: Box::new(|x| x),
+        }
+    }
+}
+
+impl<Td: 'static, Gd: 'static> Default for GeneOntologyLoader<Td, Gd> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Td, Gd, Dt, Dg> GeneOntologyLoader<Td, Gd, Dt, Dg> {
+    /// Table holding one row of data per gene.
+    pub fn gene_data(mut self, source: impl Into<TableSource>) -> Self {
+        self.gene_data = Some(source.into());
+        self
+    }
+    pub fn gene_name_column(mut self, column: impl Into<String>) -> Self {
+        self.gene_name_column = Some(column.into());
+        self
+    }
+    pub fn gene_data_column(mut self, column: impl Into<String>) -> Self {
+        self.gene_data_column = Some(column.into());
+        self
+    }
+    /// Deserialise each whole gene row, by header, into the gene data type
+    /// instead of reading a single data column. Rows are keyed by the gene name column,
+    /// so several columns such as `padj`, `log2FoldChange` and `baseMean` can be
+    /// combined in one `serde::Deserialize` struct.
+    pub fn whole_row_gene_data(mut self) -> Self {
+        self.gene_whole_row = true;
+        self
+    }
+
+    /// Table holding one row of data per term.
+    pub fn term_data(mut self, source: impl Into<TableSource>) -> Self {
+        self.term_data = Some(source.into());
+        self
+    }
+    pub fn term_name_column(mut self, column: impl Into<String>) -> Self {
+        self.term_name_column = Some(column.into());
+        self
+    }
+    pub fn term_data_column(mut self, column: impl Into<String>) -> Self {
+        self.term_data_column = Some(column.into());
+        self
+    }
+
+    /// Table holding a description, such as the full gene name, for some genes.
+    pub fn gene_descriptions(mut self, source: impl Into<TableSource>) -> Self {
+        self.gene_descriptions = Some(source.into());
+        self
+    }
+    pub fn gene_description_name_column(mut self, column: impl Into<String>) -> Self {
+        self.gene_description_name_column = Some(column.into());
+        self
+    }
+    pub fn gene_description_column(mut self, column: impl Into<String>) -> Self {
+        self.gene_description_column = Some(column.into());
+        self
+    }
+
+    /// Table holding a description, such as the GO term name, for some terms.
+    pub fn term_descriptions(mut self, source: impl Into<TableSource>) -> Self {
+        self.term_descriptions = Some(source.into());
+        self
+    }
+    pub fn term_description_name_column(mut self, column: impl Into<String>) -> Self {
+        self.term_description_name_column = Some(column.into());
+        self
+    }
+    pub fn term_description_column(mut self, column: impl Into<String>) -> Self {
+        self.term_description_column = Some(column.into());
+        self
+    }
+
+    /// Table holding the GO namespace of some terms, written as the OBO name
+    /// (`biological_process`), its abbreviation (`BP`) or the GAF aspect (`P`).
+    pub fn term_namespaces(mut self, source: impl Into<TableSource>) -> Self {
+        self.term_namespaces = Some(source.into());
+        self
+    }
+    pub fn term_namespace_name_column(mut self, column: impl Into<String>) -> Self {
+        self.term_namespace_name_column = Some(column.into());
+        self
+    }
+    pub fn term_namespace_column(mut self, column: impl Into<String>) -> Self {
+        self.term_namespace_column = Some(column.into());
+        self
+    }
+    /// Table holding one (child term, parent term) relation of the term
+    /// hierarchy per row, such as the `is_a` edges of the GO DAG.
+    pub fn term_hierarchy(mut self, source: impl Into<TableSource>) -> Self {
+        self.term_hierarchy = Some(source.into());
+        self
+    }
+    pub fn term_hierarchy_child_column(mut self, column: impl Into<String>) -> Self {
+        self.term_hierarchy_child_column = Some(column.into());
+        self
+    }
+    pub fn term_hierarchy_parent_column(mut self, column: impl Into<String>) -> Self {
+        self.term_hierarchy_parent_column = Some(column.into());
+        self
+    }
+    /// Remove terms by size and namespace once the gene universe is reconciled.
+    /// What was removed is listed in the [`LoadReport`].
+    pub fn term_filter(mut self, filter: TermFilter) -> Self {
+        self.term_filter = Some(filter);
+        self
+    }
+
+    /// Table holding one (term, gene) annotation per row.
+    pub fn adjacency(mut self, source: impl Into<TableSource>) -> Self {
+        self.adjacency = Some(source.into());
+        self
+    }
+    pub fn adjacency_term_column(mut self, column: impl Into<String>) -> Self {
+        self.adjacency_term_column = Some(column.into());
+        self
+    }
+    pub fn adjacency_gene_column(mut self, column: impl Into<String>) -> Self {
+        self.adjacency_gene_column = Some(column.into());
+        self
+    }
+
+    /// Field delimiter shared by all tables read from files or readers. Defaults to `b','`.
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Map gene identifiers in the gene data and adjacency tables to canonical IDs
+    /// before matching them, so symbols, Ensembl and Entrez IDs can be mixed.
+    ///
+    /// Unmapped and ambiguous identifiers are kept unchanged and listed in the [`LoadReport`].
+    pub fn gene_id_map(mut self, id_map: GeneIdMap) -> Self {
+        self.gene_id_map = Some(id_map);
+        self
+    }
+
+    /// Which genes are kept when the gene data and adjacency tables disagree.
+    /// Defaults to [`Universe::Intersection`]; the outcome is listed in the [`LoadReport`].
+    pub fn universe(mut self, universe: Universe) -> Self {
+        self.universe = universe;
+        self
+    }
+
+    /// Gene data fields treated as missing. Defaults to `""`, `NA`, `NaN` and `N/A`.
+    pub fn missing_values<S: Into<String>>(mut self, values: impl IntoIterator<Item = S>) -> Self {
+        self.missing_values = values.into_iter().map(|value| value.into()).collect();
+        self
+    }
+    /// How genes with missing data are handled. Defaults to [`MissingPolicy::Drop`].
+    pub fn missing_genes(mut self, policy: MissingPolicy) -> Self {
+        self.missing_policy = policy;
+        self
+    }
+    /// Data given to genes kept by [`MissingPolicy::NonHit`], such as
+    /// `Gd::from_bool(false)`, or `1.0` for adjusted p-values.
+    pub fn non_hit_gene_data<F>(mut self, f: F) -> Self
+    where
+        F: Fn() -> Gd + 'static,
+    {
+        self.non_hit_gene_data = Some(Box::new(f));
+        self
+    }
+    /// Data given to genes kept by [`MissingPolicy::Unobserved`].
+    ///
+    /// Loading panics if the data converts into a hit or a non-hit.
+    pub fn unobserved_gene_data<F>(mut self, f: F) -> Self
+    where
+        F: Fn() -> Gd + 'static,
+        Gd: Booleable + 'static,
+    {
+        self.unobserved_gene_data = Some(Box::new(move || {
+            let data = f();
+            assert!(
+                data.try_into_bool().is_none(),
+                "Unobserved gene data must not convert into a hit or a non-hit",
+            );
+            data
+        }));
+        self
+    }
+
+    /// Read gene data as `D` and convert it with `f`.
+    pub fn gene_transform<D, F>(self, f: F) -> GeneOntologyLoader<Td, Gd, Dt, D>
+    where
+        F: Fn(D) -> Gd + 'static,
+    {
+        GeneOntologyLoader {
+            gene_data: self.gene_data,
+            gene_name_column: self.gene_name_column,
+            gene_data_column: self.gene_data_column,
+            gene_whole_row: self.gene_whole_row,
+            term_data: self.term_data,
+            term_name_column: self.term_name_column,
+            term_data_column: self.term_data_column,
+            gene_descriptions: self.gene_descriptions,
+            gene_description_name_column: self.gene_description_name_column,
+            gene_description_column: self.gene_description_column,
+            term_descriptions: self.term_descriptions,
+            term_description_name_column: self.term_description_name_column,
+            term_description_column: self.term_description_column,
+            term_namespaces: self.term_namespaces,
+            term_namespace_name_column: self.term_namespace_name_column,
+            term_namespace_column: self.term_namespace_column,
+            term_hierarchy: self.term_hierarchy,
+            term_hierarchy_child_column: self.term_hierarchy_child_column,
+            term_hierarchy_parent_column: self.term_hierarchy_parent_column,
+            term_filter: self.term_filter,
+            adjacency: self.adjacency,
+            adjacency_term_column: self.adjacency_term_column,
+            adjacency_gene_column: self.adjacency_gene_column,
+            delimiter: self.delimiter,
+            gene_id_map: self.gene_id_map,
+            universe: self.universe,
+            missing_values: self.missing_values,
+            missing_policy: self.missing_policy,
+            non_hit_gene_data: self.non_hit_gene_data,
+            unobserved_gene_data: self.unobserved_gene_data,
+            gene_transform: Box::new(f),
+            term_transform: self.term_transform,
+        }
+    }
+    /// Read term data as `D` and convert it with `f`.
+    pub fn term_transform<D, F>(self, f: F) -> GeneOntologyLoader<Td, Gd, D, Dg>
+    where
+        F: Fn(D) -> Td + 'static,
+    {
+        GeneOntologyLoader {
+            gene_data: self.gene_data,
+            gene_name_column: self.gene_name_column,
+            gene_data_column: self.gene_data_column,
+            gene_whole_row: self.gene_whole_row,
+            term_data: self.term_data,
+            term_name_column: self.term_name_column,
+            term_data_column: self.term_data_column,
+            gene_descriptions: self.gene_descriptions,
+            gene_description_name_column: self.gene_description_name_column,
+            gene_description_column: self.gene_description_column,
+            term_descriptions: self.term_descriptions,
+            term_description_name_column: self.term_description_name_column,
+            term_description_column: self.term_description_column,
+            term_namespaces: self.term_namespaces,
+            term_namespace_name_column: self.term_namespace_name_column,
+            term_namespace_column: self.term_namespace_column,
+            term_hierarchy: self.term_hierarchy,
+            term_hierarchy_child_column: self.term_hierarchy_child_column,
+            term_hierarchy_parent_column: self.term_hierarchy_parent_column,
+            term_filter: self.term_filter,
+            adjacency: self.adjacency,
+            adjacency_term_column: self.adjacency_term_column,
+            adjacency_gene_column: self.adjacency_gene_column,
+            delimiter: self.delimiter,
+            gene_id_map: self.gene_id_map,
+            universe: self.universe,
+            missing_values: self.missing_values,
+            missing_policy: self.missing_policy,
+            non_hit_gene_data: self.non_hit_gene_data,
+            unobserved_gene_data: self.unobserved_gene_data,
+            gene_transform: self.gene_transform,
+            term_transform: Box::new(f),
+        }
+    }
+
+    /// Check that the configured tables and columns form a readable combination.
+    ///
+    /// Panics with a description of the first problem found.
+    pub fn validate(&self) {
+        assert!(
+            self.adjacency.is_some(),
+            "An adjacency table must be given",
+        );
+        if self.gene_whole_row {
+            assert!(
+                self.gene_name_column.is_some() && self.gene_data_column.is_none(),
+                "Whole-row gene data requires a gene name column and no gene data column",
+            );
+            assert!(
+                self.gene_data.is_some(),
+                "Whole-row gene data was requested, but no gene data table was given",
+            );
+        } else {
+            Self::validate_columns(
+                "gene", &self.gene_data,
+                &self.gene_name_column, &self.gene_data_column,
+            );
+        }
+        Self::validate_columns(
+            "term", &self.term_data,
+            &self.term_name_column, &self.term_data_column,
+        );
+        Self::validate_columns(
+            "gene description", &self.gene_descriptions,
+            &self.gene_description_name_column, &self.gene_description_column,
+        );
+        Self::validate_columns(
+            "term description", &self.term_descriptions,
+            &self.term_description_name_column, &self.term_description_column,
+        );
+        Self::validate_columns(
+            "term namespace", &self.term_namespaces,
+            &self.term_namespace_name_column, &self.term_namespace_column,
+        );
+        assert_eq!(
+            self.adjacency_term_column.is_none(),
+            self.adjacency_gene_column.is_none(),
+            "Columns must be given for either both terms and genes in the adjacency data, or for neither",
+        );
+        assert_eq!(
+            self.term_hierarchy_child_column.is_none(),
+            self.term_hierarchy_parent_column.is_none(),
+            "Columns must be given for either both children and parents in the term hierarchy, or for neither",
+        );
+        assert!(
+            self.missing_policy != MissingPolicy::NonHit || self.non_hit_gene_data.is_some(),
+            "MissingPolicy::NonHit keeps genes with missing data, so non_hit_gene_data must be given",
+        );
+        assert!(
+            self.missing_policy != MissingPolicy::Unobserved || self.unobserved_gene_data.is_some(),
+            "MissingPolicy::Unobserved keeps genes with missing data, so unobserved_gene_data must be given",
+        );
+    }
+    fn validate_columns(
+        kind: &str,
+        source: &Option<TableSource>,
+        name_column: &Option<String>, data_column: &Option<String>,
+    ) {
+        assert_eq!(
+            name_column.is_none(),
+            data_column.is_none(),
+            "Columns must be given for either both names and data in the {} data, or for neither",
+            kind,
+        );
+        assert!(
+            source.is_some() || name_column.is_none(),
+            "Columns were given for {} data, but no {} data table was given",
+            kind, kind,
+        );
+    }
+
+    /// Validate the configuration, read every table, and build the [`GeneOntology`].
+    /// Adjustments made while reading are printed as warnings.
+    pub fn load(self) -> GeneOntology<Td, Gd>
+    where
+        for<'a> Dg: serde::de::Deserialize<'a> + Default,
+        for<'a> Dt: serde::de::Deserialize<'a> + Default,
+    {
+        let (gene_ontology, report) = self.load_with_report();
+        report.print_warnings();
+        gene_ontology
+    }
+
+    /// As [`GeneOntologyLoader::load`], but returning what was adjusted while
+    /// reading instead of printing it.
+    pub fn load_with_report(self) -> (GeneOntology<Td, Gd>, LoadReport)
+    where
+        for<'a> Dg: serde::de::Deserialize<'a> + Default,
+        for<'a> Dt: serde::de::Deserialize<'a> + Default,
+    {
+        self.validate();
+
+        let mut report = LoadReport {
+            universe: self.universe,
+            missing_policy: self.missing_policy,
+            ..Default::default()
+        };
+
+        let mut adj = read_adjacency(
+            self.adjacency.unwrap(),
+            columns(&self.adjacency_term_column, &self.adjacency_gene_column),
+            self.delimiter,
+        );
+
+        let mut gene_entries = self.gene_data.map(|source| read_named_data::<Dg>(
+            source, "gene",
+            DataLayout::new(&self.gene_name_column, &self.gene_data_column, self.gene_whole_row),
+            self.delimiter,
+            &self.missing_values,
+        ));
+
+        if let Some(id_map) = &self.gene_id_map {
+            let names = gene_entries.iter()
+                .flatten()
+                .map(|(name, _)| name)
+                .chain(adj.iter().map(|(_, gene)| gene));
+            let renames = resolve_gene_ids(id_map, names, &mut report);
+
+            gene_entries = gene_entries.map(|entries| {
+                let mut seen = HashSet::new();
+                entries.into_iter()
+                    .map(|(name, data)| (renames[&name].clone(), data))
+                    .filter(|(name, _)| {
+                        let first = seen.insert(name.clone());
+                        if !first {
+                            report.merged_genes.push(name.clone());
+                        }
+                        first
+                    })
+                    .collect()
+            });
+            adj = adj.into_iter()
+                .map(|(term, gene)| (term, renames[&gene].clone()))
+                .unique()
+                .collect();
+        }
+
+        let annotated_terms: Vec<String> = adj.iter()
+            .map(|(term, _)| term.clone())
+            .unique()
+            .collect();
+
+        let missing_gene_data = match self.missing_policy {
+            MissingPolicy::Drop => None,
+            MissingPolicy::NonHit => self.non_hit_gene_data.as_ref(),
+            MissingPolicy::Unobserved => self.unobserved_gene_data.as_ref(),
+        };
+        let mut genes: Vec<(String, Gd)> = match gene_entries {
+            Some(entries) => entries.into_iter()
+                .filter_map(|(name, data)| match data {
+                    Some(data) => Some((name, (self.gene_transform)(data))),
+                    None => {
+                        report.missing_genes.push(name.clone());
+                        missing_gene_data.map(|f| (name, f()))
+                    },
+                })
+                .collect(),
+            None => adj.iter()
+                .map(|(_, gene)| gene.clone())
+                .unique()
+                .map(|gene| (gene, (self.gene_transform)(Dg::default())))
+                .collect(),
+        };
+        if self.missing_policy == MissingPolicy::Drop {
+            let dropped: HashSet<&String> = report.missing_genes.iter().collect();
+            adj.retain(|(_, gene)| !dropped.contains(gene));
+        }
+
+        reconcile_universe(
+            self.universe, &mut genes, &mut adj, &annotated_terms,
+            || (self.gene_transform)(Dg::default()),
+            &mut report,
+        );
+        let (mut gene_names, genes) = index_entries(genes);
+
+        let (mut term_names, terms) = match self.term_data {
+            Some(source) => index_entries(
+                read_named_data::<Dt>(
+                    source, "term",
+                    DataLayout::new(&self.term_name_column, &self.term_data_column, false),
+                    self.delimiter,
+                    &[],
+                )
+                .into_iter()
+                .map(|(name, data)| (
+                    name.clone(),
+                    (self.term_transform)(data.unwrap_or_else(||
+                        panic!("Term {} has missing data", name)
+                    )),
+                ))
+                .collect()
+            ),
+            None => index_entries(
+                annotated_terms.into_iter()
+                    .map(|term| (term, (self.term_transform)(Dt::default())))
+                    .collect()
+            ),
+        };
+
+        let adj = adj.iter()
+            .map(|(term, gene)| {
+                let term_idx = term_names.get(term).unwrap_or_else(||
+                    panic!("Term {} appears in the adjacency data but not in the term data", term)
+                );
+                let gene_idx = gene_names.get(gene).unwrap_or_else(||
+                    panic!("Gene {} appears in the adjacency data but not in the gene data", gene)
+                );
+                (term_idx, gene_idx)
+            })
+            .collect::<Vec<(usize, usize)>>();
+
+        if let Some(source) = self.gene_descriptions {
+            read_descriptions(
+                source, "gene",
+                DataLayout::new(&self.gene_description_name_column, &self.gene_description_column, false),
+                self.delimiter,
+            )
+            .into_iter()
+            .for_each(|(name, description)| {
+                let name = match self.gene_id_map.as_ref().map(|id_map| id_map.resolve(&name)) {
+                    Some(GeneIdResolution::Mapped(canonical)) => canonical,
+                    _ => name,
+                };
+                if let Some(idx) = gene_names.get(&name) {
+                    gene_names.set_description(idx, description);
+                }
+            });
+        }
+        if let Some(source) = self.term_descriptions {
+            read_descriptions(
+                source, "term",
+                DataLayout::new(&self.term_description_name_column, &self.term_description_column, false),
+                self.delimiter,
+            )
+            .into_iter()
+            .for_each(|(name, description)| {
+                if let Some(idx) = term_names.get(&name) {
+                    term_names.set_description(idx, description);
+                }
+            });
+        }
+
+        let mut gene_ontology = GeneOntology::from_named_incidence(gene_names, genes, term_names, terms, adj);
+        if let Some(source) = self.term_namespaces {
+            read_descriptions(
+                source, "term namespace",
+                DataLayout::new(&self.term_namespace_name_column, &self.term_namespace_column, false),
+                self.delimiter,
+            )
+            .into_iter()
+            .for_each(|(name, namespace)| {
+                let namespace: Namespace = namespace.parse()
+                    .unwrap_or_else(|err| panic!("Term {} has an invalid namespace: {}", name, err));
+                if let Some(idx) = gene_ontology.term_index(&name) {
+                    gene_ontology.set_term_namespace(idx, namespace);
+                }
+            });
+        }
+        if let Some(source) = self.term_hierarchy {
+            let relations = read_term_hierarchy(
+                source,
+                columns(&self.term_hierarchy_child_column, &self.term_hierarchy_parent_column),
+                self.delimiter,
+            );
+            report.bridged_hierarchy_terms = relate_terms(&mut gene_ontology, relations);
+            gene_ontology.validate();
+        }
+        if let Some(filter) = &self.term_filter {
+            let (filtered, filter_report) = gene_ontology.filter_terms(filter);
+            gene_ontology = filtered;
+            report.term_filter = Some(filter_report);
+        }
+
+        (gene_ontology, report)
+    }
+}
+
+fn columns<'a>(first: &'a Option<String>, second: &'a Option<String>) -> Option<(&'a str, &'a str)> {
+    match (first, second) {
+        (Some(first), Some(second)) => Some((first.as_str(), second.as_str())),
+        _ => None,
+    }
+}
+
+/// Apply `universe` to `genes` and `adj`, recording every change in `report`.
+fn reconcile_universe<Gd>(
+    universe: Universe,
+    genes: &mut Vec<(String, Gd)>,
+    adj: &mut Vec<(String, String)>,
+    annotated_terms: &[String],
+    default_gene: impl Fn() -> Gd,
+    report: &mut LoadReport,
+) {
+    let annotated: HashSet<String> = adj.iter().map(|(_, gene)| gene.clone()).collect();
+    match universe {
+        Universe::Intersection | Universe::Annotation => genes.retain(|(name, _)| {
+            let keep = annotated.contains(name);
+            if !keep {
+                report.dropped_genes.push(name.clone());
+            }
+            keep
+        }),
+        Universe::Data => genes.iter()
+            .filter(|(name, _)| !annotated.contains(name))
+            .for_each(|(name, _)| report.unannotated_genes.push(name.clone())),
+    }
+
+    let with_data: HashSet<String> = genes.iter().map(|(name, _)| name.clone()).collect();
+    let without_data: Vec<String> = adj.iter()
+        .map(|(_, gene)| gene)
+        .filter(|gene| !with_data.contains(*gene))
+        .unique()
+        .cloned()
+        .collect();
+    match universe {
+        Universe::Intersection | Universe::Data => {
+            adj.retain(|(_, gene)| with_data.contains(gene));
+            report.dropped_genes.extend(without_data);
+        },
+        Universe::Annotation => {
+            without_data.iter().for_each(|gene| genes.push((gene.clone(), default_gene())));
+            report.defaulted_genes = without_data;
+        },
+    }
+
+    let remaining: HashSet<&String> = adj.iter().map(|(term, _)| term).collect();
+    report.emptied_terms = annotated_terms.iter()
+        .filter(|term| !remaining.contains(term))
+        .cloned()
+        .collect();
+}
+
+/// Resolve every distinct name once, recording unmapped and ambiguous names in `report`.
+fn resolve_gene_ids<'a>(
+    id_map: &GeneIdMap,
+    names: impl Iterator<Item = &'a String>,
+    report: &mut LoadReport,
+) -> HashMap<String, String> {
+    let mut renames = HashMap::new();
+    names.for_each(|name| {
+        if renames.contains_key(name) {
+            return
+        }
+        let canonical = match id_map.resolve(name) {
+            GeneIdResolution::Mapped(canonical) => canonical,
+            GeneIdResolution::Unmapped => {
+                report.unmapped_genes.push(name.clone());
+                name.clone()
+            },
+            GeneIdResolution::Ambiguous(candidates) => {
+                report.ambiguous_genes.push((name.clone(), candidates));
+                name.clone()
+            },
+        };
+        renames.insert(name.clone(), canonical);
+    });
+    report.unmapped_genes.sort();
+    report.ambiguous_genes.sort();
+    renames
+}
+
+fn index_entries<D>(entries: Vec<(String, D)>) -> (NameIndex, Vec<D>) {
+    let (names, data) = entries.into_iter().unzip();
+    (NameIndex::new(names), data)
+}
+
+/// Header row and records of a [`TableSource`], with a description for error messages.
+pub(crate) struct Table {
+    pub(crate) description: String,
+    pub(crate) headers: Option<csv::StringRecord>,
+    pub(crate) records: Box<dyn Iterator<Item = Result<csv::StringRecord, csv::Error>>>,
+}
+
+impl Table {
+    pub(crate) fn open(source: TableSource, has_headers: bool, delimiter: u8) -> Self {
+        let description = source.to_string();
+        let reader: Box<dyn std::io::Read> = match source {
+            TableSource::File(file) => Box::new(
+                std::fs::File::open(&file)
+                    .unwrap_or_else(|_| panic!("File {} not found", file))
+            ),
+            TableSource::Reader(reader) => reader,
+            TableSource::Rows(rows) => {
+                let mut rows = rows.into_iter().map(csv::StringRecord::from);
+                let headers = if has_headers { rows.next() } else { None };
+                return Self {
+                    description,
+                    headers,
+                    records: Box::new(rows.map(Ok)),
+                }
+            },
+        };
+
+        let mut rdr = csv::ReaderBuilder::new()
+            .has_headers(has_headers)
+            .delimiter(delimiter)
+            .from_reader(reader);
+        let headers = if has_headers {
+            Some(
+                rdr.headers()
+                    .unwrap_or_else(|_| panic!("Could not read headers of {}", description))
+                    .clone()
+            )
+        } else {
+            None
+        };
+
+        Self {
+            description,
+            headers,
+            records: Box::new(rdr.into_records()),
+        }
+    }
+
+    /// Position of a named column.
+    pub(crate) fn column_index(&self, column: &str, description: &str) -> usize {
+        let headers = self.headers.as_ref()
+            .unwrap_or_else(|| panic!("{} has no header row", self.description));
+        headers.iter()
+            .position(|header| header == column)
+            .unwrap_or_else(|| panic!(
+                "{} expected to have column {} for {}, found {:?}",
+                self.description, column, description, headers
+            ))
+    }
+    /// Positions of the two requested columns, or the first two columns if none are named.
+    fn column_indices(&self, columns: Option<(&str, &str)>, descriptions: (&str, &str)) -> (usize, usize) {
+        match columns {
+            Some((first, second)) => (
+                self.column_index(first, descriptions.0),
+                self.column_index(second, descriptions.1),
+            ),
+            None => (0, 1),
+        }
+    }
+}
+
+/// Where the name and the data sit in each row of a named-data table.
+enum DataLayout<'a> {
+    /// No header row; the first column holds names and the second holds data.
+    Unnamed,
+    /// Names and data in the two named columns.
+    Columns(&'a str, &'a str),
+    /// Names in the named column; data deserialised from the whole row by header.
+    Row(&'a str),
+}
+
+impl<'a> DataLayout<'a> {
+    fn new(name_column: &'a Option<String>, data_column: &'a Option<String>, whole_row: bool) -> Self {
+        match (name_column, data_column) {
+            (Some(name), _) if whole_row => Self::Row(name),
+            (Some(name), Some(data)) => Self::Columns(name, data),
+            _ => Self::Unnamed,
+        }
+    }
+}
+
+fn select(record: &csv::StringRecord, idx: (usize, usize)) -> csv::StringRecord {
+    csv::StringRecord::from(vec![
+        record.get(idx.0).unwrap_or(""),
+        record.get(idx.1).unwrap_or(""),
+    ])
+}
+
+/// Read (name, data) pairs, with `None` data for entries whose data is missing.
+///
+/// With [`DataLayout::Row`], fields equal to one of `missing_values` are blanked
+/// so that `Option` fields of `D` read as `None`; an entry is only missing if
+/// it holds such a field and `D` still cannot be deserialised from it.
+fn read_named_data<D>(
+    source: TableSource,
+    kind: &str,
+    layout: DataLayout,
+    delimiter: u8,
+    missing_values: &[String],
+) -> Vec<(String, Option<D>)>
+where
+    for<'a> D: serde::de::Deserialize<'a>,
+{
+    let table = Table::open(source, !matches!(layout, DataLayout::Unnamed), delimiter);
+    let names = format!("{} names", kind);
+    let (name_idx, data_idx) = match layout {
+        DataLayout::Unnamed => (0, Some(1)),
+        DataLayout::Columns(name, data) => (
+            table.column_index(name, &names),
+            Some(table.column_index(data, &format!("{} data", kind))),
+        ),
+        DataLayout::Row(name) => (table.column_index(name, &names), None),
+    };
+    let headers = table.headers;
+    let description = table.description;
+    let is_missing = |field: &str| missing_values.iter().any(|value| value == field.trim());
+
+    let mut entries = vec![];
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    table.records.enumerate().for_each(|(entry_idx, result)| {
+        let record = result
+            .unwrap_or_else(|_| panic!("Could not read entry {} of {}", entry_idx, description));
+        let name = record.get(name_idx).unwrap_or("").to_string();
+        let data: Option<D> = match data_idx {
+            Some(data_idx) => {
+                let selected = select(&record, (name_idx, data_idx));
+                if is_missing(&selected[1]) {
+                    None
+                } else {
+                    let (_, data): (String, D) = selected
+                        .deserialize(None)
+                        .unwrap_or_else(|_|
+                            panic!(
+                                "Could not coerce entry {} of {} to (String, D)",
+                                entry_idx, description
+                            )
+                        );
+                    Some(data)
+                }
+            },
+            None => {
+                let has_missing = record.iter()
+                    .enumerate()
+                    .any(|(idx, field)| idx != name_idx && is_missing(field));
+                let blanked = csv::StringRecord::from(
+                    record.iter()
+                        .enumerate()
+                        .map(|(idx, field)| if idx != name_idx && is_missing(field) { "" } else { field })
+                        .collect::<Vec<&str>>()
+                );
+                match blanked.deserialize(headers.as_ref()) {
+                    Ok(data) => Some(data),
+                    Err(_) if has_missing => None,
+                    Err(err) => panic!(
+                        "Could not coerce entry {} of {} to D: {}",
+                        entry_idx, description, err
+                    ),
+                }
+            },
+        };
+        if let Some(first_idx) = seen.get(&name) {
+            println!(
+                "Warning: {} {} appears in data multiple times (index {} and {}). Skipping repeated entry.",
+                kind,
+                name,
+                first_idx,
+                entry_idx
+            )
+        } else {
+            seen.insert(name.clone(), entry_idx);
+            entries.push((name, data));
+        }
+    });
+
+    entries
+}
+
+/// Read (name, description) pairs, skipping empty descriptions.
+fn read_descriptions(
+    source: TableSource,
+    kind: &str,
+    layout: DataLayout,
+    delimiter: u8,
+) -> Vec<(String, String)> {
+    read_named_data::<String>(source, &format!("{} description", kind), layout, delimiter, &[])
+        .into_iter()
+        .filter_map(|(name, description)| Some((name, description?)))
+        .filter(|(_, description)| !description.is_empty())
+        .collect()
+}
+
+/// Read (child term, parent term) pairs.
+fn read_term_hierarchy(
+    source: TableSource,
+    columns: Option<(&str, &str)>,
+    delimiter: u8,
+) -> Vec<(String, String)> {
+    let table = Table::open(source, columns.is_some(), delimiter);
+    let idx = table.column_indices(columns, ("child terms", "parent terms"));
+    let description = table.description;
+
+    table.records.enumerate()
+        .map(|(entry_idx, result)| {
+            let record = result
+                .unwrap_or_else(|_| panic!("Could not read entry {} of {}", entry_idx, description));
+            select(&record, idx)
+                .deserialize(None)
+                .unwrap_or_else(|_|
+                    panic!(
+                        "Could not coerce entry {} of {} to (String, String)",
+                        entry_idx, description
+                    )
+                )
+        })
+        .collect()
+}
+
+/// Add the (child, parent) `relations` between terms of `gene_ontology`,
+/// linking terms through those it does not have, which are returned.
+fn relate_terms<Td, Gd>(gene_ontology: &mut GeneOntology<Td, Gd>, relations: Vec<(String, String)>) -> Vec<String> {
+    let nterms = gene_ontology.term_names().len();
+    let mut bridged = NameIndex::default();
+    let mut index = |name: &str| gene_ontology.term_index(name).unwrap_or_else(|| {
+        nterms + bridged.get(name).unwrap_or_else(|| bridged.push(name))
+    });
+
+    let mut parents: Vec<Vec<usize>> = vec![Vec::new(); nterms];
+    relations.iter().for_each(|(child, parent)| {
+        let (child_idx, parent_idx) = (index(child), index(parent));
+        if parents.len() <= child_idx.max(parent_idx) {
+            parents.resize(child_idx.max(parent_idx) + 1, Vec::new());
+        }
+        parents[child_idx].push(parent_idx);
+    });
+
+    let new_index: Vec<Option<usize>> = (0..parents.len()).map(|idx| (idx < nterms).then_some(idx)).collect();
+    gene_ontology.relate_terms(bridge_parents(&parents, &new_index, nterms));
+    bridged.iter().map(|name| name.to_string()).collect()
+}
+
+fn read_adjacency(
+    source: TableSource,
+    columns: Option<(&str, &str)>,
+    delimiter: u8,
+) -> Vec<(String, String)> {
+    let table = Table::open(source, columns.is_some(), delimiter);
+    let idx = table.column_indices(columns, ("term names", "gene names"));
+    let description = table.description;
+
+    let mut adj = vec![];
+    let mut adj_set = HashSet::new();
+
+    table.records.enumerate().for_each(|(entry_idx, result)| {
+        let record = result
+            .unwrap_or_else(|_| panic!("Could not read entry {} of {}", entry_idx, description));
+        let (term_name, gene_name): (String, String) = select(&record, idx)
+            .deserialize(None)
+            .unwrap_or_else(|_|
+                panic!(
+                    "Could not coerce entry {} of {} to (String, String)",
+                    entry_idx, description
+                )
+            );
+        if adj_set.contains(&(term_name.clone(), gene_name.clone())) {
+            println!(
+                "Warning: annotation ({} - {}) appears in adjacency data multiple times. Skipping repeated entry.",
+                term_name,
+                gene_name
+            )
+        } else {
+            adj.push((term_name.clone(), gene_name.clone()));
+            adj_set.insert((term_name, gene_name));
+        }
+    });
+
+    adj
+}
@@ -0,0 +1,127 @@
+use hashbrown::HashMap;
+
+/// Names and optional descriptions of a list of nodes, aligned with their indices.
+///
+/// A node can also be found under aliases, such as the IDs of terms merged into it.
+#[derive(Clone, Debug, Default)]
+pub struct NameIndex {
+    names: Vec<String>,
+    descriptions: Vec<Option<String>>,
+    aliases: Vec<Vec<String>>,
+    index: HashMap<String, usize>,
+}
+
+impl NameIndex {
+    /// Panics if a name appears more than once.
+    pub fn new(names: Vec<String>) -> Self {
+        Self::try_new(names).unwrap_or_else(|message| panic!("{}", message))
+    }
+    /// As [`NameIndex::new`], failing with a description of the repeated name.
+    pub(crate) fn try_new(names: Vec<String>) -> Result<Self, String> {
+        let mut index = HashMap::with_capacity(names.len());
+        for (idx, name) in names.iter().enumerate() {
+            if let Some(first_idx) = index.insert(name.clone(), idx) {
+                return Err(format!("Name {} given to both node {} and node {}", name, first_idx, idx))
+            }
+        }
+        Ok(Self {
+            descriptions: vec![None; names.len()],
+            aliases: vec![Vec::new(); names.len()],
+            names,
+            index,
+        })
+    }
+    /// Names nodes by their index, for graphs built without identifiers.
+    pub fn numbered(len: usize) -> Self {
+        Self::new((0..len).map(|idx| idx.to_string()).collect())
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn name(&self, idx: usize) -> Option<&str> {
+        self.names.get(idx).map(|name| name.as_str())
+    }
+    pub fn description(&self, idx: usize) -> Option<&str> {
+        self.descriptions.get(idx)?.as_deref()
+    }
+    pub fn set_description(&mut self, idx: usize, description: impl Into<String>) {
+        self.descriptions[idx] = Some(description.into());
+    }
+
+    /// Add a node called `name` after the others, returning its index. Panics
+    /// if `name` already names or is an alias of a node.
+    pub fn push(&mut self, name: impl Into<String>) -> usize {
+        let name = name.into();
+        let idx = self.names.len();
+        if let Some(other_idx) = self.index.get(&name) {
+            panic!("Name {} given to both node {} and node {}", name, other_idx, idx)
+        }
+        self.index.insert(name.clone(), idx);
+        self.names.push(name);
+        self.descriptions.push(None);
+        self.aliases.push(Vec::new());
+        idx
+    }
+    /// Remove node `idx` with its description and aliases, returning its name.
+    /// Later nodes move down one index.
+    pub fn remove(&mut self, idx: usize) -> String {
+        let name = self.names.remove(idx);
+        self.descriptions.remove(idx);
+        self.index.remove(&name);
+        self.aliases.remove(idx).iter().for_each(|alias| {
+            self.index.remove(alias);
+        });
+        self.index.values_mut()
+            .filter(|other_idx| **other_idx > idx)
+            .for_each(|other_idx| *other_idx -= 1);
+        name
+    }
+
+    /// Other names node `idx` can be found under, in the order they were added.
+    pub fn aliases(&self, idx: usize) -> &[String] {
+        self.aliases.get(idx).map_or(&[], |aliases| aliases.as_slice())
+    }
+    /// Let node `idx` also be found under `alias`. Panics if `alias` already
+    /// names or is an alias of a node.
+    pub fn add_alias(&mut self, idx: usize, alias: impl Into<String>) {
+        self.try_add_alias(idx, alias).unwrap_or_else(|message| panic!("{}", message))
+    }
+    /// As [`NameIndex::add_alias`], failing with a description of the clash.
+    pub(crate) fn try_add_alias(&mut self, idx: usize, alias: impl Into<String>) -> Result<(), String> {
+        let alias = alias.into();
+        if let Some(other_idx) = self.index.get(&alias) {
+            return Err(format!(
+                "Cannot make {} an alias of node {}, it already refers to node {}", alias, idx, other_idx
+            ))
+        }
+        self.index.insert(alias.clone(), idx);
+        self.aliases[idx].push(alias);
+        Ok(())
+    }
+
+    /// The nodes at `indices`, in that order, with their descriptions and aliases.
+    pub fn select(&self, indices: &[usize]) -> Self {
+        let mut index = Self::new(indices.iter().map(|&idx| self.names[idx].clone()).collect());
+        indices.iter()
+            .enumerate()
+            .for_each(|(new_idx, &idx)| {
+                index.descriptions[new_idx] = self.descriptions[idx].clone();
+                self.aliases[idx].iter().for_each(|alias| index.add_alias(new_idx, alias.clone()));
+            });
+        index
+    }
+
+    /// Index of the node called, or aliased, `name`.
+    pub fn get(&self, name: &str) -> Option<usize> {
+        self.index.get(name).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(|name| name.as_str())
+    }
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+use std::{fmt, str::FromStr};
+
+/// The three GO sub-ontologies a term can belong to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Namespace {
+    BiologicalProcess,
+    MolecularFunction,
+    CellularComponent,
+}
+
+impl Namespace {
+    /// The name used in OBO files and GO annotation tables.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Namespace::BiologicalProcess => "biological_process",
+            Namespace::MolecularFunction => "molecular_function",
+            Namespace::CellularComponent => "cellular_component",
+        }
+    }
+}
+
+impl fmt::Display for Namespace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Accepts the OBO names, the `BP`/`MF`/`CC` abbreviations of Bioconductor and
+/// the `P`/`F`/`C` aspects of GAF files, in any case.
+impl FromStr for Namespace {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "biological_process" | "biological process" | "bp" | "p" => Ok(Namespace::BiologicalProcess),
+            "molecular_function" | "molecular function" | "mf" | "f" => Ok(Namespace::MolecularFunction),
+            "cellular_component" | "cellular component" | "cc" | "c" => Ok(Namespace::CellularComponent),
+            _ => Err(format!("Unknown GO namespace {}", s)),
+        }
+    }
+}
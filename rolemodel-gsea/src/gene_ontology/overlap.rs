@@ -0,0 +1,198 @@
+//! Pairwise overlap between the gene sets of terms.
+//!
+//! Most pairs of terms share no gene, so overlaps are gathered by walking from
+//! each term through its genes to the other terms of those genes, and kept as a
+//! sparse matrix of the pairs that pass a threshold.
+
+use crate::gene_ontology::GeneOntology;
+
+/// Measure of how much the gene sets of two terms overlap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Similarity {
+    /// Shared genes over genes in either term.
+    Jaccard,
+    /// Shared genes over genes in the smaller term.
+    Overlap,
+    /// Number of shared genes.
+    Intersection,
+}
+
+impl Similarity {
+    pub fn score(&self, intersection: usize, size_a: usize, size_b: usize) -> f64 {
+        let denominator = match self {
+            Self::Jaccard => size_a + size_b - intersection,
+            Self::Overlap => size_a.min(size_b),
+            Self::Intersection => return intersection as f64,
+        };
+        if denominator == 0 { 0.0 } else { intersection as f64 / denominator as f64 }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Self::Jaccard => "jaccard",
+            Self::Overlap => "overlap",
+            Self::Intersection => "intersection",
+        }
+    }
+}
+
+/// Overlap between the gene sets of terms `term_a` and `term_b`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TermOverlap {
+    pub term_a: usize,
+    pub term_b: usize,
+    /// Number of genes in both terms.
+    pub intersection: usize,
+    /// Shared genes over genes in either term.
+    pub jaccard: f64,
+    /// Shared genes over genes in the smaller term.
+    pub overlap_coefficient: f64,
+}
+
+impl TermOverlap {
+    fn new(term_a: usize, term_b: usize, intersection: usize, size_a: usize, size_b: usize) -> Self {
+        Self {
+            term_a,
+            term_b,
+            intersection,
+            jaccard: Similarity::Jaccard.score(intersection, size_a, size_b),
+            overlap_coefficient: Similarity::Overlap.score(intersection, size_a, size_b),
+        }
+    }
+
+    /// The statistic `similarity` measures.
+    pub fn score(&self, similarity: Similarity) -> f64 {
+        match similarity {
+            Similarity::Jaccard => self.jaccard,
+            Similarity::Overlap => self.overlap_coefficient,
+            Similarity::Intersection => self.intersection as f64,
+        }
+    }
+}
+
+/// Sparse symmetric matrix of term overlaps, holding each pair once with
+/// `term_a < term_b`, ordered by `term_a` then `term_b`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TermOverlaps {
+    nterms: usize,
+    overlaps: Vec<TermOverlap>,
+}
+
+impl TermOverlaps {
+    /// Number of rows and columns, the number of terms of the ontology.
+    pub fn nterms(&self) -> usize {
+        self.nterms
+    }
+    /// Number of stored pairs.
+    pub fn len(&self) -> usize {
+        self.overlaps.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.overlaps.is_empty()
+    }
+
+    /// Overlap of terms `a` and `b` in either order, if it passed the threshold.
+    pub fn get(&self, a: usize, b: usize) -> Option<&TermOverlap> {
+        let key = (a.min(b), a.max(b));
+        self.overlaps
+            .binary_search_by_key(&key, |overlap| (overlap.term_a, overlap.term_b))
+            .ok()
+            .map(|position| &self.overlaps[position])
+    }
+    /// Stored overlaps involving term `idx`, whichever side it is on.
+    pub fn term(&self, idx: usize) -> impl Iterator<Item = &TermOverlap> {
+        self.overlaps.iter().filter(move |overlap| overlap.term_a == idx || overlap.term_b == idx)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &TermOverlap> {
+        self.overlaps.iter()
+    }
+}
+
+impl<Td, Gd> GeneOntology<Td, Gd> {
+    /// Overlap of the gene sets of terms `a` and `b`, found by merging their
+    /// sorted gene lists.
+    pub fn term_overlap(&self, a: usize, b: usize) -> TermOverlap {
+        let sorted_genes = |idx: usize| {
+            let mut genes = self.arena.term_genes[idx].clone();
+            genes.sort_unstable();
+            genes.dedup();
+            genes
+        };
+        let (genes_a, genes_b) = (sorted_genes(a), sorted_genes(b));
+
+        let (mut i, mut j, mut intersection) = (0, 0, 0);
+        while i < genes_a.len() && j < genes_b.len() {
+            match genes_a[i].cmp(&genes_b[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    intersection += 1;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        TermOverlap::new(a.min(b), a.max(b), intersection, genes_a.len(), genes_b.len())
+    }
+
+    /// Overlaps of every pair of distinct terms whose `similarity` is at least
+    /// `threshold`, which must be positive so that only terms sharing a gene
+    /// need to be compared.
+    pub fn term_overlaps(&self, similarity: Similarity, threshold: f64) -> TermOverlaps {
+        assert!(threshold > 0.0, "Overlap threshold must be positive");
+        let arena = &self.arena;
+        let nterms = arena.term_data.len();
+        let sizes: Vec<usize> = arena.term_genes.iter()
+            .map(|genes| {
+                let mut genes = genes.clone();
+                genes.sort_unstable();
+                genes.dedup();
+                genes.len()
+            })
+            .collect();
+
+        // Terms of each gene, sorted and without repeats, so each row only
+        // walks the terms after it.
+        let gene_terms: Vec<Vec<usize>> = arena.gene_terms.iter()
+            .map(|terms| {
+                let mut terms = terms.clone();
+                terms.sort_unstable();
+                terms.dedup();
+                terms
+            })
+            .collect();
+
+        // Shared genes with each later term, counted one row at a time. Genes
+        // seen twice in a row are skipped so repeated annotations count once.
+        let mut shared = vec![0; nterms];
+        let mut last_row = vec![usize::MAX; arena.gene_data.len()];
+        let mut overlaps = Vec::new();
+        for a in 0..nterms {
+            let mut touched = Vec::new();
+            for &gene_idx in &arena.term_genes[a] {
+                if last_row[gene_idx] == a {
+                    continue
+                }
+                last_row[gene_idx] = a;
+                let terms = &gene_terms[gene_idx];
+                terms[terms.partition_point(|&b| b <= a)..].iter()
+                    .for_each(|&b| {
+                        if shared[b] == 0 {
+                            touched.push(b);
+                        }
+                        shared[b] += 1;
+                    });
+            }
+            touched.sort_unstable();
+            touched.into_iter().for_each(|b| {
+                let overlap = TermOverlap::new(a, b, shared[b], sizes[a], sizes[b]);
+                if overlap.score(similarity) >= threshold {
+                    overlaps.push(overlap);
+                }
+                shared[b] = 0;
+            });
+        }
+
+        TermOverlaps { nterms, overlaps }
+    }
+}
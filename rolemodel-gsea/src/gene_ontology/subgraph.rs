@@ -0,0 +1,77 @@
+//! Sub-ontologies induced by a selection of terms or genes.
+//!
+//! A focused analysis, such as one restricted to immune pathways or to the
+//! genes on a panel, runs on a smaller [`GeneOntology`] holding copies of the
+//! selected nodes with their names, data and the annotations among them.
+
+use crate::gene_ontology::{arena::bridge_parents, genes::Gene, terms::Term, GeneOntology};
+
+impl<Td, Gd> GeneOntology<Td, Gd>
+where
+    Td: Clone,
+    Gd: Clone,
+{
+    /// The genes at `genes` and terms at `terms`, in that order, with their
+    /// names, data and namespaces, and every annotation between them. A term
+    /// keeps its ancestors among `terms`, through terms that are left out.
+    ///
+    /// Panics if an index is out of range or listed twice.
+    pub fn induced_subgraph(&self, genes: &[usize], terms: &[usize]) -> Self {
+        let mut local_genes = vec![None; self.arena.gene_data.len()];
+        genes.iter()
+            .enumerate()
+            .for_each(|(local_idx, &gene_idx)| local_genes[gene_idx] = Some(local_idx));
+        let local_genes = &local_genes;
+
+        let adj = terms.iter()
+            .enumerate()
+            .flat_map(|(local_idx, &term_idx)| self.arena.term_genes[term_idx].iter()
+                .filter_map(move |&gene_idx| Some((local_idx, local_genes[gene_idx]?))))
+            .collect();
+
+        let mut subgraph = Self::from_named_incidence(
+            self.gene_names.select(genes),
+            genes.iter().map(|&idx| self.arena.gene_data[idx].clone()).collect(),
+            self.term_names.select(terms),
+            terms.iter().map(|&idx| self.arena.term_data[idx].clone()).collect(),
+            adj,
+        );
+        subgraph.term_namespaces = terms.iter().map(|&idx| self.term_namespaces[idx]).collect();
+
+        let mut new_index = vec![None; self.arena.term_data.len()];
+        terms.iter()
+            .enumerate()
+            .for_each(|(local_idx, &term_idx)| new_index[term_idx] = Some(local_idx));
+        subgraph.relate_terms(bridge_parents(&self.arena.term_parents, &new_index, terms.len()));
+        subgraph
+    }
+
+    /// The terms for which `keep` is true and every gene annotated to one of them.
+    pub fn induced_by_terms(&self, mut keep: impl FnMut(&Term<Td, Gd>) -> bool) -> Self {
+        let terms: Vec<usize> = self.terms()
+            .filter(|term| keep(term))
+            .map(|term| term.index())
+            .collect();
+        let mut selected = vec![false; self.arena.gene_data.len()];
+        terms.iter()
+            .flat_map(|&term_idx| self.arena.term_genes[term_idx].iter())
+            .for_each(|&gene_idx| selected[gene_idx] = true);
+        let genes: Vec<usize> = (0..selected.len()).filter(|&idx| selected[idx]).collect();
+        self.induced_subgraph(&genes, &terms)
+    }
+
+    /// The genes for which `keep` is true and every term annotating one of
+    /// them, which keeps only its selected genes.
+    pub fn induced_by_genes(&self, mut keep: impl FnMut(&Gene<Td, Gd>) -> bool) -> Self {
+        let genes: Vec<usize> = self.genes()
+            .filter(|gene| keep(gene))
+            .map(|gene| gene.index())
+            .collect();
+        let mut selected = vec![false; self.arena.term_data.len()];
+        genes.iter()
+            .flat_map(|&gene_idx| self.arena.gene_terms[gene_idx].iter())
+            .for_each(|&term_idx| selected[term_idx] = true);
+        let terms: Vec<usize> = (0..selected.len()).filter(|&idx| selected[idx]).collect();
+        self.induced_subgraph(&genes, &terms)
+    }
+}
@@ -1,111 +1,109 @@
 use crate::{
-    gene_ontology::genes::Gene, rolemodel::saveable::Saveable, Activeable, Part, Whole, Node,
+    gene_ontology::{arena::Arena, genes::Gene},
+    HierarchicalWhole, Node, NodeMut, Whole,
 };
 
-use std::{
-    cell::{
-        Ref,
-        RefCell
-    },
-    rc::Rc,
-    ops::Deref,
-};
-
-#[derive(Debug)]
-pub struct Term<Td, Gd> {
-    data: Td,
-    genes: Vec<Rc<RefCell<Gene<Td, Gd>>>>,
+/// Handle to one term of a [`GeneOntology`](crate::GeneOntology).
+///
+/// Handles are cheap to copy and borrow the ontology's storage, so the
+/// ontology cannot change while one is alive.
+pub struct Term<'a, Td, Gd> {
+    arena: &'a Arena<Td, Gd>,
+    idx: usize,
 }
 
-impl<Td, Gd> Node for Term<Td, Gd> {
-    type Data = Td;
-    type NeighborType = Rc<RefCell<Gene<Td, Gd>>>;
-
-    fn data(&self) -> &Self::Data {
-        &self.data
+impl<'a, Td, Gd> Term<'a, Td, Gd> {
+    pub(crate) fn new(arena: &'a Arena<Td, Gd>, idx: usize) -> Self {
+        Self {
+            arena,
+            idx,
+        }
     }
-    fn data_mut(&mut self) -> &mut Self::Data {
-        &mut self.data
+    /// Position of the term in its ontology.
+    pub fn index(&self) -> usize {
+        self.idx
     }
-    fn ref_data(&self) -> Ref<Self::Data> {
-        panic!("Never call ref_data on items not in a RefCell - call data instead");
+}
+
+impl<Td, Gd> Clone for Term<'_, Td, Gd> {
+    fn clone(&self) -> Self {
+        *self
     }
-    fn iter_neighbors(&self) -> impl Iterator<Item = Self::NeighborType> {
-        self.genes.clone().into_iter()
+}
+impl<Td, Gd> Copy for Term<'_, Td, Gd> { }
+
+impl<Td, Gd> PartialEq for Term<'_, Td, Gd> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.arena, other.arena) && self.idx == other.idx
     }
 }
 
-impl<Td, Gd> Node for Rc<RefCell<Term<Td, Gd>>> {
+impl<Td: std::fmt::Debug, Gd> std::fmt::Debug for Term<'_, Td, Gd> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Term")
+            .field("index", &self.idx)
+            .field("data", self.data())
+            .finish()
+    }
+}
+
+impl<'a, Td, Gd> Node for Term<'a, Td, Gd> {
     type Data = Td;
-    type NeighborType = Rc<RefCell<Gene<Td, Gd>>>;
+    type NeighborType = Gene<'a, Td, Gd>;
 
-    fn data(&self) -> &Self::Data {
-        panic!("Cannot borrow data from inside a RefCell - use ref_data instead")
-    }
-    fn data_mut(&mut self) -> &mut Self::Data {
-        panic!("Cannot borrow data from inside a RefCell - use ref_data_mut instead")
+    fn data(&self) -> &Td {
+        &self.arena.term_data[self.idx]
     }
-    fn ref_data(&self) -> Ref<Self::Data> {
-        Ref::map(self.borrow(), |whole| &whole.data)
-    }
-    
+
     fn iter_neighbors(&self) -> impl Iterator<Item = Self::NeighborType> {
-        self.borrow().genes.clone().into_iter()
+        let arena = self.arena;
+        arena.term_genes[self.idx].iter().map(move |&idx| Gene::new(arena, idx))
     }
 }
 
-impl<Td, Gd> Term<Td, Gd> {
-    pub fn new(data: Td, genes: Vec<Rc<RefCell<Gene<Td, Gd>>>>) -> Self {
+impl<Td, Gd> Whole for Term<'_, Td, Gd> { }
+
+/// Exclusive handle to the data of one term, from [`GeneSetMut::whole_mut`](crate::GeneSetMut::whole_mut).
+pub struct TermMut<'a, Td> {
+    data: &'a mut Td,
+    idx: usize,
+}
+
+impl<'a, Td> TermMut<'a, Td> {
+    pub(crate) fn new(data: &'a mut Td, idx: usize) -> Self {
         Self {
             data,
-            genes,
+            idx,
         }
     }
-    pub fn add_gene(&mut self, gene: Rc<RefCell<Gene<Td, Gd>>>) {
-        self.genes.push(gene);
+    /// Position of the term in its ontology.
+    pub fn index(&self) -> usize {
+        self.idx
     }
 }
 
-impl<Td, Gd> Whole for Term<Td, Gd> { }
-
-impl<Td, Gd> Saveable for Term<Td, Gd> 
-where 
-    Td: Saveable,
-{
-    type Output = Td::Output;
+impl<Td> NodeMut for TermMut<'_, Td> {
+    type Data = Td;
 
-    fn save(&mut self) {
-        self.data.save();
+    fn data(&self) -> &Td {
+        self.data
     }
-    fn restore(&mut self) {
-        self.data.restore();
-    }
-    fn current(&self) -> Self::Output {
-        self.data.current()
-    }
-    fn saved(&self) -> Self::Output {
-        self.data.saved()
+    fn data_mut(&mut self) -> &mut Td {
+        self.data
     }
 }
 
-impl<Td, Gd> Whole for Rc<RefCell<Term<Td, Gd>>> { }
-
-impl<Td, Gd> Saveable for Rc<RefCell<Term<Td, Gd>>> 
-where 
-    Td: Saveable,
-{
-    type Output = Td::Output;
-
-    fn save(&mut self) {
-        (self.borrow_mut().data.save());
-    }
-    fn restore(&mut self) {
-        self.borrow_mut().data.restore();
-    }
-    fn current(&self) -> Self::Output {
-        self.data().current()
-    }
-    fn saved(&self) -> Self::Output {
-        self.data().saved()
+/// Terms have no parents or children unless a hierarchy was loaded or added.
+impl<Td, Gd> HierarchicalWhole for Term<'_, Td, Gd> {
+    fn index(&self) -> usize {
+        self.idx
+    }
+    fn parents(&self) -> impl Iterator<Item = Self> {
+        let arena = self.arena;
+        arena.term_parents[self.idx].iter().map(move |&idx| Term::new(arena, idx))
+    }
+    fn children(&self) -> impl Iterator<Item = Self> {
+        let arena = self.arena;
+        arena.term_children[self.idx].iter().map(move |&idx| Term::new(arena, idx))
     }
 }
@@ -0,0 +1,373 @@
+//! The active part of a [`GeneOntology`] as a graph for Cytoscape, Gephi or GraphViz.
+//!
+//! The exported graph holds every term whose posterior activity reaches a
+//! threshold, every gene annotated to one of those terms, and the annotations
+//! between them. Nodes carry their kind (`term` or `gene`), name, posterior
+//! activity and, for genes, observed hit status; terms also carry their
+//! description, size and number of observed hits.
+//!
+//! [`EnrichmentMap`] instead links the selected terms to each other, weighting
+//! each edge by how much their gene sets overlap.
+
+use crate::{
+    gene_ontology::overlap::Similarity,
+    report::escape,
+    rolemodel::posterior::PosteriorSummary,
+    GeneOntology, GeneSet, Node, Whole,
+};
+use hashbrown::HashMap;
+use itertools::Itertools;
+use rv::data::Booleable;
+
+use std::{
+    io::Write,
+    path::Path,
+};
+
+/// Layout of a written graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    GraphMl,
+}
+
+impl GraphFormat {
+    /// Format matching the extension of `path` (`dot`, `gv` or `graphml`).
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        match path.as_ref().extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "dot" | "gv" => Some(Self::Dot),
+            "graphml" => Some(Self::GraphMl),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubgraphTerm {
+    /// Index of the term in the ontology.
+    pub index: usize,
+    pub id: String,
+    pub name: Option<String>,
+    pub size: usize,
+    pub observed_hits: usize,
+    pub posterior_activity: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubgraphGene {
+    /// Index of the gene in the ontology.
+    pub index: usize,
+    pub symbol: String,
+    /// Whether the gene is a hit, or `None` if it is unobserved.
+    pub observed: Option<bool>,
+    pub posterior_activity: f64,
+}
+
+/// Bipartite subgraph of the terms above a posterior threshold and their genes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActiveSubgraph {
+    pub terms: Vec<SubgraphTerm>,
+    pub genes: Vec<SubgraphGene>,
+    /// Annotations as (position in `terms`, position in `genes`).
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl ActiveSubgraph {
+    /// Select the terms whose posterior activity is at least `threshold`.
+    /// Terms and genes keep their ontology order.
+    pub fn new<Td, Gd>(
+        gene_ontology: &GeneOntology<Td, Gd>,
+        posterior: &PosteriorSummary,
+        threshold: f64,
+    ) -> Self
+    where
+        Gd: Booleable,
+    {
+        let selected = select_terms(gene_ontology, posterior, threshold);
+
+        let mut covered = vec![false; gene_ontology.genes().len()];
+        selected.iter()
+            .flat_map(|(_, genes)| genes.iter())
+            .for_each(|&gene_idx| covered[gene_idx] = true);
+        let genes: Vec<SubgraphGene> = covered.iter()
+            .enumerate()
+            .filter(|(_, covered)| **covered)
+            .map(|(idx, _)| SubgraphGene {
+                index: idx,
+                symbol: gene_ontology.gene_names().name(idx).unwrap_or_default().to_string(),
+                observed: (*gene_ontology.part(idx).unwrap().data()).try_into_bool(),
+                posterior_activity: posterior.gene_activity(idx),
+            })
+            .collect();
+        let positions: HashMap<usize, usize> = genes.iter()
+            .enumerate()
+            .map(|(position, gene)| (gene.index, position))
+            .collect();
+
+        let terms = selected.iter()
+            .map(|(idx, term_genes)| subgraph_term(gene_ontology, posterior, *idx, term_genes))
+            .collect();
+        let edges = selected.iter()
+            .enumerate()
+            .flat_map(|(term_position, (_, term_genes))| term_genes.iter()
+                .map(|gene_idx| (term_position, positions[gene_idx]))
+                .collect::<Vec<(usize, usize)>>())
+            .collect();
+
+        Self { terms, genes, edges }
+    }
+
+    pub fn write(&self, writer: impl Write, format: GraphFormat) -> std::io::Result<()> {
+        match format {
+            GraphFormat::Dot => self.write_dot(writer),
+            GraphFormat::GraphMl => self.write_graphml(writer),
+        }
+    }
+
+    /// Write the graph, choosing the format from the file extension.
+    pub fn write_file(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let path = path.as_ref();
+        let format = GraphFormat::from_path(path).ok_or_else(|| std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Cannot infer a graph format from {}", path.display()),
+        ))?;
+        self.write(std::io::BufWriter::new(std::fs::File::create(path)?), format)
+    }
+
+    /// Write an undirected GraphViz graph with terms drawn as boxes and genes as
+    /// ellipses, observed hits filled red.
+    pub fn write_dot(&self, mut writer: impl Write) -> std::io::Result<()> {
+        writeln!(writer, "graph active_terms {{")?;
+        writeln!(writer, "  node [style=filled, fillcolor=white];")?;
+        for (position, term) in self.terms.iter().enumerate() {
+            write_dot_term(&mut writer, position, term)?;
+        }
+        for (position, gene) in self.genes.iter().enumerate() {
+            writeln!(
+                writer,
+                "  g{} [shape=ellipse, kind=\"gene\", label=\"{}\", id=\"{}\", observed=\"{}\", posterior={}, fillcolor=\"{}\"];",
+                position, dot_escape(&gene.symbol), dot_escape(&gene.symbol), observed_label(gene.observed),
+                gene.posterior_activity, if gene.observed == Some(true) { "#f4a582" } else { "white" },
+            )?;
+        }
+        for (term_position, gene_position) in self.edges.iter() {
+            writeln!(writer, "  t{} -- g{};", term_position, gene_position)?;
+        }
+        writeln!(writer, "}}")?;
+        writer.flush()
+    }
+
+    /// Write GraphML with typed node attributes, as read by Cytoscape and Gephi.
+    pub fn write_graphml(&self, mut writer: impl Write) -> std::io::Result<()> {
+        write_graphml_header(&mut writer, "active_terms", &[])?;
+        for (position, term) in self.terms.iter().enumerate() {
+            write_graphml_term(&mut writer, position, term)?;
+        }
+        for (position, gene) in self.genes.iter().enumerate() {
+            writeln!(writer, "    <node id=\"g{}\">", position)?;
+            writeln!(writer, "      <data key=\"kind\">gene</data>")?;
+            writeln!(writer, "      <data key=\"label\">{}</data>", escape(&gene.symbol))?;
+            writeln!(writer, "      <data key=\"observed\">{}</data>", observed_label(gene.observed))?;
+            writeln!(writer, "      <data key=\"posterior\">{}</data>", gene.posterior_activity)?;
+            writeln!(writer, "    </node>")?;
+        }
+        for (term_position, gene_position) in self.edges.iter() {
+            writeln!(writer, "    <edge source=\"t{}\" target=\"g{}\"/>", term_position, gene_position)?;
+        }
+        writeln!(writer, "  </graph>\n</graphml>")?;
+        writer.flush()
+    }
+}
+
+/// Enrichment map: terms above a posterior threshold, linked when their gene
+/// sets are similar enough. Node weights are the terms' posterior activities.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnrichmentMap {
+    pub terms: Vec<SubgraphTerm>,
+    /// Links as (position in `terms`, position in `terms`, similarity), with
+    /// the first position smaller than the second.
+    pub edges: Vec<(usize, usize, f64)>,
+    pub similarity: Similarity,
+}
+
+impl EnrichmentMap {
+    /// Link every pair of terms with posterior activity at least `term_threshold`
+    /// whose `similarity` is at least `similarity_threshold`, which must be positive.
+    pub fn new<Td, Gd>(
+        gene_ontology: &GeneOntology<Td, Gd>,
+        posterior: &PosteriorSummary,
+        term_threshold: f64,
+        similarity: Similarity,
+        similarity_threshold: f64,
+    ) -> Self
+    where
+        Gd: Booleable,
+    {
+        assert!(similarity_threshold > 0.0, "Similarity threshold must be positive");
+        let selected = select_terms(gene_ontology, posterior, term_threshold);
+
+        let mut position = vec![None; gene_ontology.terms().len()];
+        selected.iter()
+            .enumerate()
+            .for_each(|(term_position, (idx, _))| position[*idx] = Some(term_position));
+        let edges = gene_ontology.term_overlaps(similarity, similarity_threshold)
+            .iter()
+            .filter_map(|overlap| Some((
+                position[overlap.term_a]?,
+                position[overlap.term_b]?,
+                overlap.score(similarity),
+            )))
+            .collect();
+
+        let terms = selected.iter()
+            .map(|(idx, term_genes)| subgraph_term(gene_ontology, posterior, *idx, term_genes))
+            .collect();
+
+        Self { terms, edges, similarity }
+    }
+
+    pub fn write(&self, writer: impl Write, format: GraphFormat) -> std::io::Result<()> {
+        match format {
+            GraphFormat::Dot => self.write_dot(writer),
+            GraphFormat::GraphMl => self.write_graphml(writer),
+        }
+    }
+
+    /// Write the map, choosing the format from the file extension.
+    pub fn write_file(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let path = path.as_ref();
+        let format = GraphFormat::from_path(path).ok_or_else(|| std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Cannot infer a graph format from {}", path.display()),
+        ))?;
+        self.write(std::io::BufWriter::new(std::fs::File::create(path)?), format)
+    }
+
+    /// Write an undirected GraphViz graph whose edges carry the similarity as
+    /// `weight`, `penwidth` growing with it.
+    pub fn write_dot(&self, mut writer: impl Write) -> std::io::Result<()> {
+        writeln!(writer, "graph enrichment_map {{")?;
+        writeln!(writer, "  node [style=filled, fillcolor=white];")?;
+        for (position, term) in self.terms.iter().enumerate() {
+            write_dot_term(&mut writer, position, term)?;
+        }
+        for (a, b, score) in self.edges.iter() {
+            writeln!(
+                writer, "  t{} -- t{} [{}={}, weight={}, penwidth={:.2}];",
+                a, b, self.similarity.name(), score, score, 1.0 + 4.0 * score,
+            )?;
+        }
+        writeln!(writer, "}}")?;
+        writer.flush()
+    }
+
+    /// Write GraphML whose edges carry the similarity as a `double` attribute
+    /// named after the measure.
+    pub fn write_graphml(&self, mut writer: impl Write) -> std::io::Result<()> {
+        let key = self.similarity.name();
+        write_graphml_header(&mut writer, "enrichment_map", &[(key, "double")])?;
+        for (position, term) in self.terms.iter().enumerate() {
+            write_graphml_term(&mut writer, position, term)?;
+        }
+        for (a, b, score) in self.edges.iter() {
+            writeln!(writer, "    <edge source=\"t{}\" target=\"t{}\">", a, b)?;
+            writeln!(writer, "      <data key=\"{}\">{}</data>", key, score)?;
+            writeln!(writer, "    </edge>")?;
+        }
+        writeln!(writer, "  </graph>\n</graphml>")?;
+        writer.flush()
+    }
+}
+
+/// Terms whose posterior activity reaches `threshold`, with the indices of their genes.
+fn select_terms<Td, Gd>(
+    gene_ontology: &GeneOntology<Td, Gd>,
+    posterior: &PosteriorSummary,
+    threshold: f64,
+) -> Vec<(usize, Vec<usize>)> {
+    assert_eq!(posterior.nterms(), gene_ontology.terms().len(), "Posterior must cover every term");
+    assert_eq!(posterior.ngenes(), gene_ontology.genes().len(), "Posterior must cover every gene");
+
+    gene_ontology.terms()
+        .filter(|term| posterior.term_activity(term.index()) >= threshold)
+        .map(|term| (term.index(), term.parts().map(|gene| gene.index()).unique().collect()))
+        .collect()
+}
+
+fn subgraph_term<Td, Gd>(
+    gene_ontology: &GeneOntology<Td, Gd>,
+    posterior: &PosteriorSummary,
+    idx: usize,
+    term_genes: &[usize],
+) -> SubgraphTerm
+where
+    Gd: Booleable,
+{
+    SubgraphTerm {
+        index: idx,
+        id: gene_ontology.term_names().name(idx).unwrap_or_default().to_string(),
+        name: gene_ontology.term_names().description(idx).map(|name| name.to_string()),
+        size: term_genes.len(),
+        observed_hits: term_genes.iter()
+            .filter(|&&gene_idx| (*gene_ontology.part(gene_idx).unwrap().data()).try_into_bool() == Some(true))
+            .count(),
+        posterior_activity: posterior.term_activity(idx),
+    }
+}
+
+fn write_dot_term(writer: &mut impl Write, position: usize, term: &SubgraphTerm) -> std::io::Result<()> {
+    writeln!(
+        writer,
+        "  t{} [shape=box, kind=\"term\", label=\"{}\", id=\"{}\", name=\"{}\", size={}, observed_hits={}, posterior={}];",
+        position, dot_escape(&term.id), dot_escape(&term.id), dot_escape(term.name.as_deref().unwrap_or("")),
+        term.size, term.observed_hits, term.posterior_activity,
+    )
+}
+
+/// Write the GraphML preamble with the node attribute keys and any extra
+/// `(name, type)` edge attribute keys, and open the graph.
+fn write_graphml_header(writer: &mut impl Write, graph_id: &str, edge_keys: &[(&str, &str)]) -> std::io::Result<()> {
+    writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(writer, "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">")?;
+    let node_keys = [
+        ("kind", "string"),
+        ("label", "string"),
+        ("name", "string"),
+        ("size", "int"),
+        ("observed_hits", "int"),
+        ("observed", "string"),
+        ("posterior", "double"),
+    ];
+    for (key, kind) in node_keys {
+        writeln!(writer, "  <key id=\"{}\" for=\"node\" attr.name=\"{}\" attr.type=\"{}\"/>", key, key, kind)?;
+    }
+    for (key, kind) in edge_keys {
+        writeln!(writer, "  <key id=\"{}\" for=\"edge\" attr.name=\"{}\" attr.type=\"{}\"/>", key, key, kind)?;
+    }
+    writeln!(writer, "  <graph id=\"{}\" edgedefault=\"undirected\">", graph_id)
+}
+
+fn write_graphml_term(writer: &mut impl Write, position: usize, term: &SubgraphTerm) -> std::io::Result<()> {
+    writeln!(writer, "    <node id=\"t{}\">", position)?;
+    writeln!(writer, "      <data key=\"kind\">term</data>")?;
+    writeln!(writer, "      <data key=\"label\">{}</data>", escape(&term.id))?;
+    if let Some(name) = term.name.as_deref() {
+        writeln!(writer, "      <data key=\"name\">{}</data>", escape(name))?;
+    }
+    writeln!(writer, "      <data key=\"size\">{}</data>", term.size)?;
+    writeln!(writer, "      <data key=\"observed_hits\">{}</data>", term.observed_hits)?;
+    writeln!(writer, "      <data key=\"posterior\">{}</data>", term.posterior_activity)?;
+    writeln!(writer, "    </node>")
+}
+
+fn observed_label(observed: Option<bool>) -> &'static str {
+    match observed {
+        Some(true) => "hit",
+        Some(false) => "non-hit",
+        None => "unobserved",
+    }
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -2,15 +2,35 @@
 //! 
 //! A library for conducting multiset Gene Set Enrichment Calculations on gene expression data.
 
-use std::cell::Ref;
+use hashbrown::{HashMap, HashSet};
 
 /// A trait for running GSEA calculations on gene expression data.
+///
+/// Parts and wholes are handed out as handles borrowing the gene set, so the
+/// gene set cannot change while any of them is alive. Their data is changed
+/// through [`GeneSetMut`] instead.
 pub trait GeneSet {
-    type PartNode;
-    type WholeNode;
+    type PartData;
+    type WholeData;
+    type PartNode<'a>: Part<Data = Self::PartData, NeighborType = Self::WholeNode<'a>> where Self: 'a;
+    type WholeNode<'a>: Whole<Data = Self::WholeData, NeighborType = Self::PartNode<'a>> where Self: 'a;
 
-    fn iter_parts(&self) -> impl Iterator<Item = Self::PartNode>;
-    fn iter_wholes(&self) -> impl Iterator<Item = Self::WholeNode>;
+    fn iter_parts(&self) -> impl Iterator<Item = Self::PartNode<'_>>;
+    fn iter_wholes(&self) -> impl Iterator<Item = Self::WholeNode<'_>>;
+
+    fn n_parts(&self) -> usize { self.iter_parts().count() }
+    fn n_wholes(&self) -> usize { self.iter_wholes().count() }
+    fn part(&self, idx: usize) -> Option<Self::PartNode<'_>> { self.iter_parts().nth(idx) }
+    fn whole(&self, idx: usize) -> Option<Self::WholeNode<'_>> { self.iter_wholes().nth(idx) }
+}
+
+/// A [`GeneSet`] whose node data can be changed, one node at a time.
+pub trait GeneSetMut: GeneSet {
+    type PartNodeMut<'a>: NodeMut<Data = Self::PartData> where Self: 'a;
+    type WholeNodeMut<'a>: NodeMut<Data = Self::WholeData> where Self: 'a;
+
+    fn part_mut(&mut self, idx: usize) -> Option<Self::PartNodeMut<'_>>;
+    fn whole_mut(&mut self, idx: usize) -> Option<Self::WholeNodeMut<'_>>;
 }
 
 pub trait Node {
@@ -18,21 +38,32 @@ pub trait Node {
     type NeighborType: Node;
 
     fn data(&self) -> &Self::Data;
-    fn data_mut(&mut self) -> &mut Self::Data;
-    fn ref_data(&self) -> Ref<Self::Data>;
     fn iter_neighbors(&self) -> impl Iterator<Item = Self::NeighborType>;
 }
-impl<N: Node> Activeable for N
+/// Exclusive access to the data of one node, as handed out by [`GeneSetMut`].
+///
+/// A `NodeMut` holds its gene set mutably borrowed, so no other node can
+/// see its data change. For the same reason it does not walk to neighbours.
+pub trait NodeMut {
+    type Data;
+
+    fn data(&self) -> &Self::Data;
+    fn data_mut(&mut self) -> &mut Self::Data;
+}
+impl<N: NodeMut + ?Sized> NodeMut for &mut N {
+    type Data = N::Data;
+
+    fn data(&self) -> &Self::Data { (**self).data() }
+    fn data_mut(&mut self) -> &mut Self::Data { (**self).data_mut() }
+}
+impl<N: NodeMut> Activeable for N
 where 
-    <N as Node>::Data: Activeable,
-    <<N as Node>::NeighborType as Node>::Data: Activeable,
+    <N as NodeMut>::Data: Activeable,
 {
     fn is_active(&self) -> bool { self.data().is_active() }
     fn set_activity(&mut self, b: bool) { self.data_mut().set_activity(b)}
     
-    fn is_legal(&self) -> bool {
-        todo!()
-    }
+    fn is_legal(&self) -> bool { self.data().is_legal() }
 }
 // impl<N: Node> Saveable for N
 // where
@@ -81,11 +112,71 @@ pub trait Whole: Node
     // fn is_legal(&self) -> bool { self.data().is_legal() }
     // fn switch_activity(&mut self, b: bool) { self.data_mut().set_activity(b)}
 }
+/// A [`Whole`] placed in a hierarchy of wholes, such as a GO term in the GO
+/// DAG, where parents are more general than their children.
+///
+/// The hierarchy must be acyclic; [`HierarchicalWhole::depth`] panics on a cycle.
+pub trait HierarchicalWhole: Whole + Sized {
+    /// Position among all wholes, identifying the whole while walking the hierarchy.
+    fn index(&self) -> usize;
+    fn parents(&self) -> impl Iterator<Item = Self>;
+    fn children(&self) -> impl Iterator<Item = Self>;
+
+    /// Every whole above this one, each once, nearest first.
+    fn ancestors(&self) -> impl Iterator<Item = Self> {
+        walk_hierarchy(self, |whole| whole.parents().collect()).into_iter()
+    }
+    /// Every whole below this one, each once, nearest first.
+    fn descendants(&self) -> impl Iterator<Item = Self> {
+        walk_hierarchy(self, |whole| whole.children().collect()).into_iter()
+    }
+    /// Number of steps on the longest path up to a whole without parents.
+    fn depth(&self) -> usize {
+        fn longest<W: HierarchicalWhole>(whole: &W, depths: &mut HashMap<usize, Option<usize>>) -> usize {
+            match depths.get(&whole.index()) {
+                Some(Some(depth)) => return *depth,
+                Some(None) => panic!("Hierarchy has a cycle through whole {}", whole.index()),
+                None => {},
+            }
+            depths.insert(whole.index(), None);
+            let depth = whole.parents()
+                .map(|parent| longest(&parent, depths) + 1)
+                .max()
+                .unwrap_or(0);
+            depths.insert(whole.index(), Some(depth));
+            depth
+        }
+        longest(self, &mut HashMap::new())
+    }
+}
+
+/// Breadth-first walk from `start` along `next`, visiting every whole once.
+fn walk_hierarchy<W: HierarchicalWhole>(start: &W, next: impl Fn(&W) -> Vec<W>) -> Vec<W> {
+    let mut seen: HashSet<usize> = HashSet::from_iter([start.index()]);
+    let mut walked: Vec<W> = Vec::new();
+    let mut frontier = next(start);
+    while !frontier.is_empty() {
+        frontier.retain(|whole| seen.insert(whole.index()));
+        let following = frontier.iter().flat_map(&next).collect();
+        walked.append(&mut frontier);
+        frontier = following;
+    }
+    walked
+}
 
 mod gene_ontology;
 pub use gene_ontology::{
-    genes::Gene, 
-    terms::Term, 
+    genes::{Gene, GeneMut}, 
+    terms::{Term, TermMut}, 
+    loader::{GeneOntologyLoader, LoadReport, MissingPolicy, TableSource, Universe},
+    id_map::{GeneIdMap, GeneIdResolution},
+    names::NameIndex,
+    namespace::Namespace,
+    filter::{TermFilter, TermFilterReport},
+    collapse::CollapseReport,
+    components::Component,
+    overlap::{Similarity, TermOverlap, TermOverlaps},
+    graph::{GeneOntGraph, GraphNode, GraphGene, GraphTerm},
     GeneOntology,
     GOGeneListRolemodel,
 };
@@ -96,7 +187,26 @@ pub use rolemodel::{
     saveable::SaveableData,
     genelist::GeneListRolemodel,
     activeable::Activeable,
+    posterior::PosteriorSummary,
+    trace::{Draw, TraceWriter, read_trace},
+    cmdstan::{CmdStanHeader, CmdStanWriter},
+    checkpoint::{ChainRng, Checkpoint},
+    diagnostics::{split_rhat, effective_sample_size},
+};
+mod output;
+pub use output::{
+    ResultTables,
+    TermResult,
+    GeneResult,
+    TableFormat,
+};
+mod report;
+pub use report::HtmlReport;
+mod graph_export;
+pub use graph_export::{
+    ActiveSubgraph,
+    SubgraphTerm,
+    SubgraphGene,
+    GraphFormat,
+    EnrichmentMap,
 };
-pub use gene_ontology::{genes::Gene, terms::Term, GeneOntology};
-mod rolemodel;
-mod unsafe_rolemodel;
@@ -0,0 +1,197 @@
+//! Per-term and per-gene result tables for a finished run.
+//!
+//! Rows follow the node order of the [`GeneOntology`], columns follow the
+//! field order of [`TermResult`] and [`GeneResult`], and floats are rounded to
+//! a fixed number of decimal places, so tables from repeated runs can be diffed.
+
+use crate::{
+    rolemodel::posterior::PosteriorSummary,
+    GeneOntology, Node, Part, Whole,
+};
+use rv::data::Booleable;
+use serde::Serialize;
+
+use std::{
+    io::Write,
+    path::Path,
+};
+
+/// Layout of a written table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableFormat {
+    Csv,
+    Tsv,
+    /// An array with one object per row.
+    Json,
+}
+
+impl TableFormat {
+    /// Format matching the extension of `path` (`csv`, `tsv` or `json`).
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        match path.as_ref().extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "tsv" | "txt" => Some(Self::Tsv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TermResult {
+    pub id: String,
+    pub name: Option<String>,
+    /// Number of genes annotated to the term.
+    pub size: usize,
+    /// Number of those genes observed as hits.
+    pub observed_hits: usize,
+    pub posterior_activity: f64,
+    /// Monte Carlo standard error of `posterior_activity`.
+    pub mcse: f64,
+    /// IDs of terms with the same genes that were collapsed into this one, separated by `;`.
+    pub merged_terms: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GeneResult {
+    pub symbol: String,
+    /// Whether the gene is a hit, or `None` if it is unobserved.
+    pub observed: Option<bool>,
+    pub posterior_activity: f64,
+    /// Terms containing the gene whose posterior activity reaches the
+    /// explanation threshold, most probable first, separated by `;`.
+    pub explaining_terms: String,
+}
+
+/// Result tables for every term and gene of a [`GeneOntology`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResultTables {
+    pub terms: Vec<TermResult>,
+    pub genes: Vec<GeneResult>,
+    precision: usize,
+}
+
+impl ResultTables {
+    /// Tabulate `posterior` against `gene_ontology`. A term explains a gene it
+    /// contains when its posterior activity is at least `explain_threshold`.
+    pub fn new<Td, Gd>(
+        gene_ontology: &GeneOntology<Td, Gd>,
+        posterior: &PosteriorSummary,
+        explain_threshold: f64,
+    ) -> Self
+    where
+        Gd: Booleable,
+    {
+        assert_eq!(posterior.nterms(), gene_ontology.terms().len(), "Posterior must cover every term");
+        assert_eq!(posterior.ngenes(), gene_ontology.genes().len(), "Posterior must cover every gene");
+
+        let observed: Vec<Option<bool>> = gene_ontology.genes()
+            .map(|gene| (*gene.data()).try_into_bool())
+            .collect();
+
+        let terms = gene_ontology.terms()
+            .enumerate()
+            .map(|(idx, term)| TermResult {
+                id: gene_ontology.term_names().name(idx).unwrap_or_default().to_string(),
+                name: gene_ontology.term_names().description(idx).map(|name| name.to_string()),
+                size: term.parts().count(),
+                observed_hits: term.parts()
+                    .filter(|gene| (*gene.data()).try_into_bool() == Some(true))
+                    .count(),
+                posterior_activity: posterior.term_activity(idx),
+                mcse: posterior.term_mcse(idx),
+                merged_terms: gene_ontology.term_names().aliases(idx).join(";"),
+            })
+            .collect::<Vec<TermResult>>();
+
+        let genes = gene_ontology.genes()
+            .enumerate()
+            .map(|(idx, gene)| {
+                let mut explaining = gene.wholes()
+                    .filter_map(|term| {
+                        let activity = posterior.term_activity(term.index());
+                        (activity >= explain_threshold).then_some((term.index(), activity))
+                    })
+                    .collect::<Vec<(usize, f64)>>();
+                explaining.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+                GeneResult {
+                    symbol: gene_ontology.gene_names().name(idx).unwrap_or_default().to_string(),
+                    observed: observed[idx],
+                    posterior_activity: posterior.gene_activity(idx),
+                    explaining_terms: explaining.iter()
+                        .map(|(term_idx, _)| terms[*term_idx].id.as_str())
+                        .collect::<Vec<&str>>()
+                        .join(";"),
+                }
+            })
+            .collect();
+
+        Self {
+            terms,
+            genes,
+            precision: 6,
+        }
+    }
+
+    /// Number of decimal places floats are rounded to. Defaults to 6.
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    pub fn write_terms(&self, writer: impl Write, format: TableFormat) -> std::io::Result<()> {
+        let rows = self.terms.iter().map(|row| TermResult {
+            posterior_activity: round(row.posterior_activity, self.precision),
+            mcse: round(row.mcse, self.precision),
+            ..row.clone()
+        });
+        write_rows(writer, format, rows)
+    }
+    pub fn write_genes(&self, writer: impl Write, format: TableFormat) -> std::io::Result<()> {
+        let rows = self.genes.iter().map(|row| GeneResult {
+            posterior_activity: round(row.posterior_activity, self.precision),
+            ..row.clone()
+        });
+        write_rows(writer, format, rows)
+    }
+
+    /// Write both tables, choosing each format from its file extension.
+    pub fn write_files(&self, terms_path: impl AsRef<Path>, genes_path: impl AsRef<Path>) -> std::io::Result<()> {
+        let format = |path: &Path| TableFormat::from_path(path).ok_or_else(|| std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Cannot infer a table format from {}", path.display()),
+        ));
+        let terms_path = terms_path.as_ref();
+        let genes_path = genes_path.as_ref();
+        self.write_terms(std::fs::File::create(terms_path)?, format(terms_path)?)?;
+        self.write_genes(std::fs::File::create(genes_path)?, format(genes_path)?)
+    }
+}
+
+fn round(x: f64, precision: usize) -> f64 {
+    let scale = 10f64.powi(precision as i32);
+    (x * scale).round() / scale
+}
+
+fn write_rows<R: Serialize>(
+    mut writer: impl Write,
+    format: TableFormat,
+    rows: impl Iterator<Item = R>,
+) -> std::io::Result<()> {
+    match format {
+        TableFormat::Csv | TableFormat::Tsv => {
+            let mut wtr = csv::WriterBuilder::new()
+                .delimiter(if format == TableFormat::Csv { b',' } else { b'\t' })
+                .from_writer(writer);
+            for row in rows {
+                wtr.serialize(row)?;
+            }
+            wtr.flush()
+        },
+        TableFormat::Json => {
+            serde_json::to_writer_pretty(&mut writer, &rows.collect::<Vec<R>>())?;
+            writeln!(writer)
+        },
+    }
+}
@@ -0,0 +1,296 @@
+//! Single-file HTML report of a finished run, readable offline in any browser.
+//!
+//! The report holds the run parameters, convergence diagnostics, the most
+//! probable terms with the genes they contain, and trace plots drawn as inline
+//! SVG. It has no scripts and loads nothing from the network.
+
+use crate::{
+    output::ResultTables,
+    rolemodel::{
+        diagnostics::{effective_sample_size, split_rhat},
+        posterior::PosteriorSummary,
+        trace::Draw,
+        Rolemodel,
+    },
+    GeneOntology, GeneSet, Node, Whole,
+};
+use rv::data::Booleable;
+
+use std::{
+    fmt::Write as _,
+    io::Write,
+    path::Path,
+};
+
+const STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em auto; max-width: 60em; color: #222; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { border-bottom: 1px solid #ddd; padding: 0.25em 0.75em; text-align: left; }
+td.num { text-align: right; font-variant-numeric: tabular-nums; }
+.hit { font-weight: bold; color: #b2182b; }
+.unobserved { color: #888; }
+.warn { color: #b2182b; }
+details { margin: 0.25em 0; }
+svg { display: block; margin: 1em 0; }
+";
+
+const COLOURS: [&str; 6] = ["#1b9e77", "#d95f02", "#7570b3", "#e7298a", "#66a61e", "#e6ab02"];
+
+/// HTML report of one run, built from its ontology, posterior summary and the
+/// retained draws of each chain.
+pub struct HtmlReport<'a, Td, Gd> {
+    gene_ontology: &'a GeneOntology<Td, Gd>,
+    posterior: &'a PosteriorSummary,
+    chains: &'a [Vec<Draw>],
+    title: String,
+    parameters: Vec<(String, String)>,
+    top_terms: usize,
+}
+
+impl<'a, Td, Gd> HtmlReport<'a, Td, Gd>
+where
+    Gd: Booleable,
+{
+    pub fn new(
+        gene_ontology: &'a GeneOntology<Td, Gd>,
+        posterior: &'a PosteriorSummary,
+        chains: &'a [Vec<Draw>],
+    ) -> Self {
+        Self {
+            gene_ontology,
+            posterior,
+            chains,
+            title: "Rolemodel run".to_string(),
+            parameters: Vec::new(),
+            top_terms: 50,
+        }
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+    /// Add a row to the run parameters table.
+    pub fn parameter(mut self, name: impl Into<String>, value: impl std::fmt::Display) -> Self {
+        self.parameters.push((name.into(), value.to_string()));
+        self
+    }
+    /// Add the sampler settings and hyperparameters of `model` to the run parameters.
+    pub fn model_parameters(self, model: &impl Rolemodel) -> Self {
+        let report = self
+            .parameter("burn_in", model.burn_in())
+            .parameter("nsamples", model.nsamples())
+            .parameter("thinning", model.thinning());
+        model.hyperparameters().into_iter()
+            .fold(report, |report, (name, value)| report.parameter(name, value))
+    }
+    /// Number of most probable terms listed. Defaults to 50.
+    pub fn top_terms(mut self, top_terms: usize) -> Self {
+        self.top_terms = top_terms;
+        self
+    }
+
+    pub fn write(&self, mut writer: impl Write) -> std::io::Result<()> {
+        writer.write_all(self.render().as_bytes())?;
+        writer.flush()
+    }
+    pub fn write_file(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        self.write(std::io::BufWriter::new(std::fs::File::create(path)?))
+    }
+
+    /// The whole report as an HTML document.
+    pub fn render(&self) -> String {
+        let mut html = String::new();
+        writeln!(html, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">").unwrap();
+        writeln!(html, "<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>", escape(&self.title), STYLE).unwrap();
+        writeln!(html, "<h1>{}</h1>", escape(&self.title)).unwrap();
+
+        self.render_parameters(&mut html);
+        self.render_diagnostics(&mut html);
+        self.render_traces(&mut html);
+        self.render_terms(&mut html);
+
+        writeln!(html, "</body>\n</html>").unwrap();
+        html
+    }
+
+    fn render_parameters(&self, html: &mut String) {
+        writeln!(html, "<h2>Run parameters</h2>\n<table>").unwrap();
+        let counts = [
+            ("genes", self.gene_ontology.genes().len().to_string()),
+            ("terms", self.gene_ontology.terms().len().to_string()),
+            ("chains", self.chains.len().to_string()),
+            ("retained draws", self.posterior.ndraws().to_string()),
+        ];
+        counts.iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .chain(self.parameters.iter().cloned())
+            .for_each(|(name, value)| {
+                writeln!(html, "<tr><th>{}</th><td>{}</td></tr>", escape(&name), escape(&value)).unwrap()
+            });
+        writeln!(html, "</table>").unwrap();
+    }
+
+    fn render_diagnostics(&self, html: &mut String) {
+        writeln!(html, "<h2>Convergence diagnostics</h2>").unwrap();
+        writeln!(html, "<table>\n<tr><th>quantity</th><th>split R&#770;</th><th>effective sample size</th></tr>").unwrap();
+        [("log posterior", self.series(|draw| draw.log_posterior)),
+         ("active terms", self.series(|draw| draw.active_terms.len() as f64))]
+            .iter()
+            .for_each(|(name, chains)| {
+                let rhat = split_rhat(chains);
+                let class = if rhat > 1.01 { " warn" } else { "" };
+                writeln!(
+                    html, "<tr><td>{}</td><td class=\"num{}\">{}</td><td class=\"num\">{}</td></tr>",
+                    name, class, format_stat(rhat, 3), format_stat(effective_sample_size(chains), 0),
+                ).unwrap();
+            });
+        writeln!(html, "</table>").unwrap();
+
+        let max_mcse = (0..self.posterior.nterms())
+            .map(|idx| self.posterior.term_mcse(idx))
+            .fold(0.0, f64::max);
+        writeln!(
+            html, "<p>Largest Monte Carlo standard error of a term activity: {}.</p>",
+            format_stat(max_mcse, 4),
+        ).unwrap();
+    }
+
+    fn render_traces(&self, html: &mut String) {
+        writeln!(html, "<h2>Traces</h2>").unwrap();
+        html.push_str(&svg_trace("Log posterior", &self.points(|draw| draw.log_posterior)));
+        html.push_str(&svg_trace("Active terms", &self.points(|draw| draw.active_terms.len() as f64)));
+    }
+
+    fn render_terms(&self, html: &mut String) {
+        let tables = ResultTables::new(self.gene_ontology, self.posterior, 1.0);
+        let mut order: Vec<usize> = (0..tables.terms.len()).collect();
+        order.sort_by(|&a, &b| tables.terms[b].posterior_activity
+            .total_cmp(&tables.terms[a].posterior_activity)
+            .then(a.cmp(&b)));
+        order.truncate(self.top_terms);
+
+        writeln!(html, "<h2>Top {} terms</h2>", order.len()).unwrap();
+        writeln!(
+            html,
+            "<table>\n<tr><th>term</th><th>name</th><th>size</th><th>observed hits</th>\
+             <th>posterior activity</th><th>MCSE</th></tr>",
+        ).unwrap();
+        order.iter().for_each(|&idx| {
+            let row = &tables.terms[idx];
+            writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td>\
+                 <td class=\"num\">{:.3}</td><td class=\"num\">{:.3}</td></tr>",
+                escape(&row.id), escape(row.name.as_deref().unwrap_or("")),
+                row.size, row.observed_hits, row.posterior_activity, row.mcse,
+            ).unwrap();
+        });
+        writeln!(html, "</table>").unwrap();
+
+        writeln!(html, "<h2>Gene membership</h2>").unwrap();
+        writeln!(html, "<p>Observed hits are <span class=\"hit\">highlighted</span>; \
+                        <span class=\"unobserved\">grey</span> genes are unobserved.</p>").unwrap();
+        order.iter().for_each(|&idx| {
+            let row = &tables.terms[idx];
+            writeln!(
+                html, "<details>\n<summary>{} {} ({} of {} genes observed as hits)</summary>",
+                escape(&row.id), escape(row.name.as_deref().unwrap_or("")), row.observed_hits, row.size,
+            ).unwrap();
+            writeln!(html, "<table>\n<tr><th>gene</th><th>observed</th><th>posterior activity</th></tr>").unwrap();
+            self.gene_ontology.whole(idx).unwrap().parts().for_each(|gene| {
+                let gene_idx = gene.index();
+                let (class, observed) = match (*gene.data()).try_into_bool() {
+                    Some(true) => ("hit", "hit"),
+                    Some(false) => ("", "not a hit"),
+                    None => ("unobserved", "unobserved"),
+                };
+                writeln!(
+                    html, "<tr class=\"{}\"><td>{}</td><td>{}</td><td class=\"num\">{:.3}</td></tr>",
+                    class, escape(&tables.genes[gene_idx].symbol), observed, self.posterior.gene_activity(gene_idx),
+                ).unwrap();
+            });
+            writeln!(html, "</table>\n</details>").unwrap();
+        });
+    }
+
+    fn series(&self, value: impl Fn(&Draw) -> f64) -> Vec<Vec<f64>> {
+        self.chains.iter().map(|chain| chain.iter().map(&value).collect()).collect()
+    }
+    fn points(&self, value: impl Fn(&Draw) -> f64) -> Vec<Vec<(f64, f64)>> {
+        self.chains.iter()
+            .map(|chain| chain.iter().map(|draw| (draw.iteration as f64, value(draw))).collect())
+            .collect()
+    }
+}
+
+fn format_stat(x: f64, decimals: usize) -> String {
+    if x.is_finite() {
+        format!("{:.*}", decimals, x)
+    } else if x.is_nan() {
+        "n/a".to_string()
+    } else {
+        "&infin;".to_string()
+    }
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.chars().fold(String::with_capacity(text.len()), |mut escaped, c| {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+        escaped
+    })
+}
+
+/// Line plot of one series per chain against the iteration, with the value
+/// and iteration ranges labelled on the axes.
+fn svg_trace(title: &str, chains: &[Vec<(f64, f64)>]) -> String {
+    let (width, height, margin) = (720.0, 220.0, 50.0);
+    let points = chains.iter().flatten();
+    let (x_min, x_max) = points.clone().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| (lo.min(p.0), hi.max(p.0)));
+    let (y_min, y_max) = points.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| (lo.min(p.1), hi.max(p.1)));
+
+    let mut svg = String::new();
+    writeln!(
+        svg, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" role=\"img\" aria-label=\"{}\">",
+        width, height, escape(title),
+    ).unwrap();
+    writeln!(svg, "<text x=\"{}\" y=\"16\" font-size=\"14\">{}</text>", margin, escape(title)).unwrap();
+    writeln!(
+        svg, "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"#999\"/>",
+        margin, 25.0, width - 2.0 * margin, height - margin - 25.0,
+    ).unwrap();
+
+    if x_min.is_finite() && y_min.is_finite() {
+        let x_span = if x_max > x_min { x_max - x_min } else { 1.0 };
+        let y_span = if y_max > y_min { y_max - y_min } else { 1.0 };
+        let x = |value: f64| margin + (value - x_min) / x_span * (width - 2.0 * margin);
+        let y = |value: f64| height - margin - (value - y_min) / y_span * (height - margin - 25.0);
+
+        chains.iter().enumerate().for_each(|(chain_idx, chain)| {
+            let coordinates: Vec<String> = chain.iter()
+                .map(|&(px, py)| format!("{:.1},{:.1}", x(px), y(py)))
+                .collect();
+            writeln!(
+                svg, "<polyline fill=\"none\" stroke=\"{}\" stroke-width=\"1\" points=\"{}\"/>",
+                COLOURS[chain_idx % COLOURS.len()], coordinates.join(" "),
+            ).unwrap();
+        });
+
+        let label = |value: f64| format!("{:.4}", value).trim_end_matches('0').trim_end_matches('.').to_string();
+        writeln!(svg, "<text x=\"{}\" y=\"{}\" font-size=\"11\" text-anchor=\"end\">{}</text>", margin - 4.0, y(y_max) + 4.0, label(y_max)).unwrap();
+        writeln!(svg, "<text x=\"{}\" y=\"{}\" font-size=\"11\" text-anchor=\"end\">{}</text>", margin - 4.0, y(y_min), label(y_min)).unwrap();
+        writeln!(svg, "<text x=\"{}\" y=\"{}\" font-size=\"11\">{}</text>", margin, height - margin + 14.0, label(x_min)).unwrap();
+        writeln!(svg, "<text x=\"{}\" y=\"{}\" font-size=\"11\" text-anchor=\"end\">{}</text>", width - margin, height - margin + 14.0, label(x_max)).unwrap();
+        writeln!(svg, "<text x=\"{}\" y=\"{}\" font-size=\"11\" text-anchor=\"middle\">iteration</text>", width / 2.0, height - margin + 28.0).unwrap();
+    }
+
+    writeln!(svg, "</svg>").unwrap();
+    svg
+}
@@ -1,8 +1,20 @@
 pub mod genelist;
 pub mod saveable;
 pub mod activeable;
+pub mod posterior;
+pub mod trace;
+pub mod cmdstan;
+pub mod checkpoint;
+pub mod diagnostics;
 
 use crate::{Activeable, Part, Whole};
+use trace::Draw;
+
+use rand::Rng;
+use std::{
+    collections::BTreeMap,
+    ops::RangeInclusive,
+};
 
 
 
@@ -13,6 +25,31 @@ pub trait Rolemodel {
     fn thinning(&self) -> usize;
 
     fn draw_samples(&mut self);
+    /// Run the chain with `rng`: `burn_in` steps, then `nsamples` retained draws
+    /// taken `thinning` steps apart, each passed to `on_draw` as soon as it is made.
+    fn draw_samples_with<R: Rng + ?Sized>(&mut self, rng: &mut R, on_draw: impl FnMut(&Self, &Draw));
+    /// Bring derived state, such as part activity and the cached posterior,
+    /// in line with the current activity of the wholes.
+    fn start_chain(&mut self);
+    /// Run the given steps of a started chain, numbered from 1 as in
+    /// [`Rolemodel::draw_samples_with`]. Running a chain in consecutive pieces
+    /// with the same `rng` retains the same draws as running it in one go.
+    fn run_iterations<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+        iterations: RangeInclusive<usize>,
+        on_draw: impl FnMut(&Self, &Draw),
+    );
+    /// Total number of steps in a chain, `burn_in + nsamples * thinning`.
+    fn niterations(&self) -> usize {
+        self.burn_in() + self.nsamples() * self.thinning().max(1)
+    }
+    /// Propose one change of state and accept or reject it, returning whether it was accepted.
+    fn step<R: Rng + ?Sized>(&mut self, rng: &mut R) -> bool;
+    /// The current state as a draw taken at `iteration`.
+    fn current_draw(&self, iteration: usize) -> Draw;
+
+    fn hyperparameters(&self) -> BTreeMap<String, f64>;
 
     fn calc_posterior_llikelihood(&mut self) -> f64;
     fn posterior_llikelihood(&self) -> f64;
@@ -0,0 +1,81 @@
+use super::saveable::SaveableData;
+use crate::gene_ontology::binary::{invalid_data, read_framed, write_framed};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+/// Random number generator whose state can be written to a checkpoint.
+pub type ChainRng = rand_xoshiro::Xoshiro256PlusPlus;
+
+/// Magic bytes starting a checkpoint file.
+const MAGIC: &[u8; 4] = b"RMCP";
+/// Version of the checkpoint layout, increased whenever it changes.
+const FORMAT_VERSION: u32 = 1;
+
+/// Complete state of a chain after `iteration` steps.
+///
+/// Restoring a checkpoint and running the remaining steps with `rng` continues
+/// the chain exactly as if it had never been stopped.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Checkpoint<Td, Gd> {
+    /// Number of steps completed, counting burn-in steps.
+    pub iteration: usize,
+    pub rng: ChainRng,
+
+    pub burn_in: usize,
+    pub nsamples: usize,
+    pub thinning: usize,
+    pub hyperparameters: BTreeMap<String, f64>,
+
+    pub posterior_llikelihood: SaveableData<f64>,
+    /// Data of every term, holding its activity, in term index order.
+    pub term_data: Vec<Td>,
+    /// Data of every gene, holding its activity, in gene index order.
+    pub gene_data: Vec<Gd>,
+}
+
+impl<Td, Gd> Checkpoint<Td, Gd>
+where
+    Td: Serialize + DeserializeOwned,
+    Gd: Serialize + DeserializeOwned,
+{
+    /// Write the checkpoint as a versioned, checksummed binary file, which
+    /// keeps non-finite values such as a log posterior of `-inf` exactly.
+    pub fn write(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        let payload = postcard::to_stdvec(self)
+            .map_err(|err| invalid_data(format!("Could not encode checkpoint: {}", err)))?;
+        write_framed(writer, MAGIC, FORMAT_VERSION, &payload)
+    }
+
+    /// Read a checkpoint written by [`Checkpoint::write`]. Fails with
+    /// [`std::io::ErrorKind::InvalidData`] if the file is not a checkpoint,
+    /// has another format version, or does not match its checksum.
+    pub fn read(reader: impl Read) -> std::io::Result<Self> {
+        let payload = read_framed(reader, MAGIC, FORMAT_VERSION, "Checkpoint")?;
+        postcard::from_bytes(&payload)
+            .map_err(|err| invalid_data(format!("Could not decode checkpoint: {}", err)))
+    }
+
+    /// Write the checkpoint to `path`, replacing it only once the whole
+    /// checkpoint is written, so a run killed mid-write keeps the previous one.
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let path = path.as_ref();
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+
+        let mut writer = BufWriter::new(File::create(&partial)?);
+        self.write(&mut writer)?;
+        writer.get_ref().sync_all()?;
+        std::fs::rename(&partial, path)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+}
@@ -0,0 +1,196 @@
+//! Chains written in the output CSV layout of CmdStan's `sample` method.
+//!
+//! Each chain goes to its own file, which starts with `#` comment lines
+//! describing the run, followed by a header row and one row per retained draw.
+//! The columns are `lp__`, one per hyperparameter, and one activity indicator
+//! `term.<k>` per term, numbered from 1 as CmdStan numbers array elements.
+//! Tools reading CmdStan output, such as ArviZ's `from_cmdstan`, treat the
+//! indicators as a `term` array variable.
+//!
+//! The comment block only holds `key = value` fields CmdStan itself writes for
+//! `sample` and whose values hold for a Rolemodel run. Neither the
+//! `stan_version_*` fields nor `algorithm` are written: the file does not come
+//! from Stan, and the Metropolis-Hastings sampler is none of CmdStan's
+//! algorithms, so readers must not take it for a Stan run.
+
+use super::{trace::Draw, Rolemodel};
+use crate::GeneSet;
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+/// Run description written as the comment block of a CmdStan CSV.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CmdStanHeader {
+    chain_id: usize,
+    model_name: String,
+    num_warmup: usize,
+    num_samples: usize,
+    thin: usize,
+    seed: Option<u64>,
+    hyperparameters: Vec<String>,
+    term_names: Vec<Option<String>>,
+}
+
+impl CmdStanHeader {
+    /// Header for chain `chain_id` of a model with `nterms` terms and no hyperparameters.
+    pub fn new(chain_id: usize, nterms: usize) -> Self {
+        Self {
+            chain_id,
+            model_name: "rolemodel_gsea".to_string(),
+            num_warmup: 0,
+            num_samples: 0,
+            thin: 1,
+            seed: None,
+            hyperparameters: Vec::new(),
+            term_names: vec![None; nterms],
+        }
+    }
+    /// Header taking the sampler settings, hyperparameters and term count from `model`.
+    pub fn from_model<M: Rolemodel + GeneSet>(model: &M, chain_id: usize) -> Self {
+        Self::new(chain_id, model.n_wholes())
+            .sampler(model.burn_in(), model.nsamples(), model.thinning())
+            .hyperparameters(model.hyperparameters().into_keys())
+    }
+
+    pub fn model_name(mut self, model_name: impl Into<String>) -> Self {
+        self.model_name = model_name.into();
+        self
+    }
+    pub fn sampler(mut self, num_warmup: usize, num_samples: usize, thin: usize) -> Self {
+        self.num_warmup = num_warmup;
+        self.num_samples = num_samples;
+        self.thin = thin.max(1);
+        self
+    }
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+    /// Names of the hyperparameter columns, in the order they are written.
+    pub fn hyperparameters(mut self, names: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.hyperparameters = names.into_iter().map(|name| name.into()).collect();
+        self
+    }
+    /// Term identifiers, listed in the comment block so indicator columns can be
+    /// matched back to terms. Panics if the number of names differs from the number of terms.
+    pub fn term_names<'a>(mut self, names: impl IntoIterator<Item = &'a str>) -> Self {
+        let names: Vec<Option<String>> = names.into_iter().map(|name| Some(name.to_string())).collect();
+        assert_eq!(
+            names.len(), self.term_names.len(),
+            "Got {} term names for {} terms", names.len(), self.term_names.len()
+        );
+        self.term_names = names;
+        self
+    }
+
+    pub fn chain_id(&self) -> usize {
+        self.chain_id
+    }
+    pub fn nterms(&self) -> usize {
+        self.term_names.len()
+    }
+
+    /// Path of this chain's file, `<prefix>_<chain_id>.csv` as CmdStan names per-chain outputs.
+    pub fn chain_path(&self, prefix: impl AsRef<Path>) -> PathBuf {
+        let prefix = prefix.as_ref();
+        let file_name = format!(
+            "{}_{}.csv",
+            prefix.file_name().map(|name| name.to_string_lossy()).unwrap_or_default(),
+            self.chain_id,
+        );
+        prefix.with_file_name(file_name)
+    }
+
+    fn write(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        writeln!(writer, "# model = {}", self.model_name)?;
+        writeln!(writer, "# method = sample (Default)")?;
+        writeln!(writer, "#   sample")?;
+        writeln!(writer, "#     num_samples = {}", self.num_samples)?;
+        writeln!(writer, "#     num_warmup = {}", self.num_warmup)?;
+        writeln!(writer, "#     save_warmup = 0")?;
+        writeln!(writer, "#     thin = {}", self.thin)?;
+        writeln!(writer, "#     adapt")?;
+        writeln!(writer, "#       engaged = 0")?;
+        writeln!(writer, "# id = {}", self.chain_id)?;
+        if let Some(seed) = self.seed {
+            writeln!(writer, "# random")?;
+            writeln!(writer, "#   seed = {}", seed)?;
+        }
+        // Without `=`, so readers parsing the block do not take these for CmdStan fields.
+        for (idx, name) in self.term_names.iter().enumerate() {
+            if let Some(name) = name {
+                writeln!(writer, "# term.{}: {}", idx + 1, name)?;
+            }
+        }
+
+        let columns = std::iter::once("lp__".to_string())
+            .chain(self.hyperparameters.iter().cloned())
+            .chain((1..=self.nterms()).map(|k| format!("term.{}", k)))
+            .collect::<Vec<String>>();
+        writeln!(writer, "{}", columns.join(","))
+    }
+}
+
+/// Writes the retained draws of one chain as a CmdStan CSV.
+pub struct CmdStanWriter<W: Write> {
+    writer: W,
+    header: CmdStanHeader,
+    row: Vec<u8>,
+}
+
+impl CmdStanWriter<BufWriter<File>> {
+    pub fn create(path: impl AsRef<Path>, header: CmdStanHeader) -> std::io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?), header)
+    }
+    /// Create the file of `header`'s chain next to `prefix`, see [`CmdStanHeader::chain_path`].
+    pub fn create_chain(prefix: impl AsRef<Path>, header: CmdStanHeader) -> std::io::Result<Self> {
+        Self::create(header.chain_path(prefix), header)
+    }
+}
+
+impl<W: Write> CmdStanWriter<W> {
+    /// Write the comment block and column header to `writer`.
+    pub fn new(mut writer: W, header: CmdStanHeader) -> std::io::Result<Self> {
+        header.write(&mut writer)?;
+        Ok(Self { writer, header, row: Vec::new() })
+    }
+
+    /// Write one row. Panics if the draw's hyperparameters differ from the
+    /// header's or it activates a term beyond the header's term count.
+    pub fn write_draw(&mut self, draw: &Draw) -> std::io::Result<()> {
+        assert!(
+            draw.hyperparameters.len() == self.header.hyperparameters.len()
+                && self.header.hyperparameters.iter().all(|name| draw.hyperparameters.contains_key(name)),
+            "Draw at iteration {} has hyperparameters {:?}, expected {:?}",
+            draw.iteration, draw.hyperparameters.keys().collect::<Vec<&String>>(), self.header.hyperparameters
+        );
+
+        let nterms = self.header.nterms();
+        let mut active = vec![false; nterms];
+        draw.active_terms.iter().for_each(|&idx| {
+            assert!(idx < nterms, "Draw at iteration {} activates term {} of {}", draw.iteration, idx, nterms);
+            active[idx] = true;
+        });
+
+        self.row.clear();
+        write!(self.row, "{}", draw.log_posterior)?;
+        for name in self.header.hyperparameters.iter() {
+            write!(self.row, ",{}", draw.hyperparameters[name])?;
+        }
+        for active in active {
+            write!(self.row, ",{}", active as u8)?;
+        }
+        self.row.push(b'\n');
+        self.writer.write_all(&self.row)
+    }
+
+    /// Flush the chain and return the underlying writer.
+    pub fn finish(mut self) -> std::io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
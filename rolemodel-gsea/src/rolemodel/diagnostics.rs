@@ -0,0 +1,92 @@
+//! Convergence diagnostics for a scalar quantity traced over several chains.
+//!
+//! Both diagnostics follow the rank-free definitions of Gelman et al.,
+//! *Bayesian Data Analysis* (3rd ed.), section 11.4–11.5. Chains are truncated
+//! to the length of the shortest one.
+
+fn mean(x: &[f64]) -> f64 {
+    x.iter().sum::<f64>() / x.len() as f64
+}
+
+fn variance(x: &[f64]) -> f64 {
+    let mean = mean(x);
+    x.iter().map(|xi| (xi - mean).powi(2)).sum::<f64>() / (x.len() as f64 - 1.0)
+}
+
+/// Within-chain variance W and the pooled variance estimate var+.
+fn variance_components(chains: &[&[f64]]) -> (f64, f64) {
+    let n = chains[0].len() as f64;
+    let within = chains.iter().map(|chain| variance(chain)).sum::<f64>() / chains.len() as f64;
+    let means: Vec<f64> = chains.iter().map(|chain| mean(chain)).collect();
+    let between_over_n = if chains.len() > 1 { variance(&means) } else { 0.0 };
+    (within, (n - 1.0) / n * within + between_over_n)
+}
+
+fn truncate(chains: &[Vec<f64>]) -> Vec<&[f64]> {
+    let n = chains.iter().map(|chain| chain.len()).min().unwrap_or(0);
+    chains.iter().map(|chain| &chain[..n]).collect()
+}
+
+/// Potential scale reduction factor computed on each chain split in half.
+///
+/// Values close to 1 indicate the chains have mixed. Returns NaN if any half
+/// chain has fewer than two draws, and 1 if every draw is identical.
+pub fn split_rhat(chains: &[Vec<f64>]) -> f64 {
+    let chains = truncate(chains);
+    let half = chains.first().map(|chain| chain.len() / 2).unwrap_or(0);
+    if half < 2 {
+        return f64::NAN
+    }
+    let halves: Vec<&[f64]> = chains.iter()
+        .flat_map(|chain| [&chain[..half], &chain[chain.len() - half..]])
+        .collect();
+
+    let (within, var_plus) = variance_components(&halves);
+    if within == 0.0 {
+        return if var_plus == 0.0 { 1.0 } else { f64::INFINITY }
+    }
+    (var_plus / within).sqrt()
+}
+
+/// Effective number of independent draws across all chains.
+///
+/// Autocorrelations are summed in consecutive pairs until a pair sum turns
+/// negative (Geyer's initial positive sequence). Returns NaN if the chains have
+/// fewer than four draws, and the total number of draws if every draw is identical.
+pub fn effective_sample_size(chains: &[Vec<f64>]) -> f64 {
+    let chains = truncate(chains);
+    let n = chains.first().map(|chain| chain.len()).unwrap_or(0);
+    if n < 4 {
+        return f64::NAN
+    }
+    let ndraws = (chains.len() * n) as f64;
+
+    let (within, var_plus) = variance_components(&chains);
+    if var_plus == 0.0 {
+        return ndraws
+    }
+
+    let means: Vec<f64> = chains.iter().map(|chain| mean(chain)).collect();
+    let autocorrelation = |lag: usize| {
+        let autocovariance = chains.iter()
+            .zip(means.iter())
+            .map(|(chain, mean)| (0..n - lag)
+                .map(|i| (chain[i] - mean) * (chain[i + lag] - mean))
+                .sum::<f64>() / n as f64)
+            .sum::<f64>() / chains.len() as f64;
+        1.0 - (within - autocovariance) / var_plus
+    };
+
+    let mut tau = -1.0;
+    let mut lag = 0;
+    while lag + 1 < n {
+        let pair = autocorrelation(lag) + autocorrelation(lag + 1);
+        if pair <= 0.0 {
+            break
+        }
+        tau += 2.0 * pair;
+        lag += 2;
+    }
+    // As in Stan, antithetic chains are credited with at most log10 of the draw count per draw.
+    ndraws / tau.max(1.0 / ndraws.log10())
+}
@@ -1,13 +1,18 @@
 use rv::data::Booleable;
 use crate::{
-    Activeable, GeneSet, Node, Part, SaveableData, Whole
+    Activeable, GeneSet, GeneSetMut, Node, Part, SaveableData
 };
 
 use super::{
     saveable::Saveable, 
+    trace::Draw,
     Rolemodel,
 };
 use rand::Rng;
+use std::{
+    collections::BTreeMap,
+    ops::RangeInclusive,
+};
 
 // pub trait GeneListEnrichment: GeneSet
 // where 
@@ -19,15 +24,10 @@ use rand::Rng;
 // }
 
 
-pub trait GeneListRolemodel: GeneSet
+pub trait GeneListRolemodel: GeneSetMut
 where 
-    <Self as GeneSet>::PartNode: Part,
-    <<Self as GeneSet>::PartNode as Node>::Data: Activeable + Booleable,
-    <<Self as GeneSet>::PartNode as Node>::NeighborType: Whole,
-    <<<Self as GeneSet>::PartNode as Node>::NeighborType as Node>::Data: Activeable,
-    <Self as GeneSet>::WholeNode: Whole,
-    <<Self as GeneSet>::WholeNode as Node>::Data: Activeable,
-    <<<Self as GeneSet>::WholeNode as Node>::NeighborType as Node>::Data: Activeable,
+    <Self as GeneSet>::PartData: Activeable + Booleable,
+    <Self as GeneSet>::WholeData: Activeable,
 {
     fn burn_in(&self) -> usize;
     fn nsamples(&self) -> usize;
@@ -39,13 +39,23 @@ where
     fn illegal_set_penalty(&self) -> f64;
 
     fn posterior_llikelihood(&self) -> &SaveableData<f64>;
+    fn posterior_llikelihood_mut(&mut self) -> &mut SaveableData<f64>;
+
+    fn hyperparameters(&self) -> BTreeMap<String, f64> {
+        BTreeMap::from([
+            ("set_activity_probability".to_string(), self.set_activity_probability()),
+            ("true_active_gene_hit_rate".to_string(), self.true_active_gene_hit_rate()),
+            ("false_inactive_gene_hit_rate".to_string(), self.false_inactive_gene_hit_rate()),
+            ("illegal_set_penalty".to_string(), self.illegal_set_penalty()),
+        ])
+    }
 
     fn calc_llikelihood_wholes(&self) -> f64 {
         let mut n_illegal = 0.0;
         self.iter_wholes()
             .map(|whole| {
-                if whole.is_illegal() {n_illegal += 1.0}
-                match whole.is_active() {
+                if whole.data().is_illegal() {n_illegal += 1.0}
+                match whole.data().is_active() {
                     true => {
                         self.set_activity_probability().ln()
                     },
@@ -56,49 +66,125 @@ where
             })
             .sum::<f64>() - self.illegal_set_penalty() * n_illegal
     }
+    /// Parts whose data is unobserved (`try_into_bool` gives `None`) are
+    /// marginalised out, contributing nothing to the likelihood.
     fn calc_llikelihood_parts(&self) -> f64 {
         self.iter_parts()
-            .map(|part| {
-                let activity = part.is_active();
-                match (activity, part.data().try_into_bool().expect("Data should be binary 0/1")) {
+            .filter_map(|part| {
+                let activity = part.data().is_active();
+                let hit = part.data().try_into_bool()?;
+                Some(match (activity, hit) {
                     (true, true) => self.true_active_gene_hit_rate().ln(),
                     (true, false) => (1.0 - self.true_active_gene_hit_rate()).ln(),
                     (false, true) => self.false_inactive_gene_hit_rate().ln(),
                     (false, false) => (1.0 - self.false_inactive_gene_hit_rate()).ln()
-                }
+                })
             })
             .sum()
     }
 }
 
+/// Make every part active exactly when one of its wholes is. The activities are
+/// read first, as changing a part needs the gene set mutably borrowed.
+fn update_part_activity<G>(gene_set: &mut G)
+where
+    G: GeneSetMut,
+    <G as GeneSet>::PartData: Activeable,
+    <G as GeneSet>::WholeData: Activeable,
+{
+    let activities: Vec<bool> = gene_set.iter_parts()
+        .map(|part| part.wholes().any(|whole| whole.data().is_active()))
+        .collect();
+    activities.into_iter()
+        .enumerate()
+        .for_each(|(idx, active)| gene_set.part_mut(idx).unwrap().set_activity(active));
+}
 
 impl<G> Rolemodel for G
 where 
     G: GeneListRolemodel + Saveable,
-    <G as GeneSet>::PartNode: Part,
-    <<G as GeneSet>::PartNode as Node>::Data: Booleable + Activeable,
-    <<G as GeneSet>::PartNode as Node>::NeighborType: Whole,
-    <<<G as GeneSet>::PartNode as Node>::NeighborType as Node>::Data: Activeable,
-    <G as GeneSet>::WholeNode: Whole,
-    <<G as GeneSet>::WholeNode as Node>::Data: Activeable,
-    <<<Self as GeneSet>::WholeNode as Node>::NeighborType as Node>::Data: Activeable,
+    <G as GeneSet>::PartData: Booleable + Activeable,
+    <G as GeneSet>::WholeData: Activeable,
 {
+    fn draw_samples(&mut self) {
+        self.draw_samples_with(&mut rand::thread_rng(), |_, _| {})
+    }
 
+    fn draw_samples_with<R: Rng + ?Sized>(&mut self, rng: &mut R, on_draw: impl FnMut(&Self, &Draw)) {
+        self.start_chain();
+        let niterations = self.niterations();
+        self.run_iterations(rng, 1..=niterations, on_draw);
+    }
 
-    fn draw_samples(&mut self) {
-        (0..self.nsamples()).for_each(|_| {
-            self.save();
+    fn start_chain(&mut self) {
+        update_part_activity(self);
+        let post_ll = self.calc_posterior_llikelihood();
+        *GeneListRolemodel::posterior_llikelihood_mut(self) = SaveableData::new(post_ll, post_ll);
+    }
 
-            let old_post_ll = self.calc_posterior_llikelihood();
-            todo!()
-        }); 
+    fn run_iterations<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+        iterations: RangeInclusive<usize>,
+        mut on_draw: impl FnMut(&Self, &Draw),
+    ) {
+        let burn_in = GeneListRolemodel::burn_in(self);
+        let thinning = GeneListRolemodel::thinning(self).max(1);
+
+        for iteration in iterations {
+            self.step(rng);
+            if iteration > burn_in && (iteration - burn_in).is_multiple_of(thinning) {
+                let draw = self.current_draw(iteration);
+                on_draw(self, &draw);
+            }
+        }
+    }
+
+    /// Flip the activity of one uniformly chosen whole, update the activity of
+    /// every part to match, and accept with the Metropolis-Hastings probability.
+    fn step<R: Rng + ?Sized>(&mut self, rng: &mut R) -> bool {
+        let nwholes = self.n_wholes();
+        if nwholes == 0 {
+            return false
+        }
+        let old_post_ll = GeneListRolemodel::posterior_llikelihood(self).current();
+
+        self.save();
+        self.whole_mut(rng.gen_range(0..nwholes)).unwrap().switch_activity();
+        update_part_activity(self);
+
+        let new_post_ll = self.calc_posterior_llikelihood();
+        let accept = new_post_ll >= old_post_ll || rng.gen::<f64>() < (new_post_ll - old_post_ll).exp();
+        if accept {
+            GeneListRolemodel::posterior_llikelihood_mut(self).set(new_post_ll);
+        } else {
+            self.restore();
+        }
+        accept
+    }
+
+    fn current_draw(&self, iteration: usize) -> Draw {
+        Draw {
+            iteration,
+            log_posterior: Rolemodel::posterior_llikelihood(self),
+            hyperparameters: Rolemodel::hyperparameters(self),
+            active_terms: self.iter_wholes()
+                .enumerate()
+                .filter(|(_, whole)| whole.data().is_active())
+                .map(|(idx, _)| idx)
+                .collect(),
+        }
+    }
+
+    fn hyperparameters(&self) -> BTreeMap<String, f64> {
+        GeneListRolemodel::hyperparameters(self)
     }
 
     fn calc_posterior_llikelihood(&mut self) -> f64 {
-        0.0    
+        self.calc_prior_llikelihood() + self.calc_data_llikelihood()
     }
     fn posterior_llikelihood(&self) -> f64 {
-        todo!()
+        GeneListRolemodel::posterior_llikelihood(self).current()
     }
 
     fn calc_prior_llikelihood(&mut self) -> f64 {
@@ -116,14 +202,14 @@ where
     }
     
     fn burn_in(&self) -> usize  {
-        self.burn_in()
+        GeneListRolemodel::burn_in(self)
     }
     
     fn nsamples(&self) -> usize {
-        self.nsamples()
+        GeneListRolemodel::nsamples(self)
     }
     
     fn thinning(&self) -> usize {
-        self.thinning()
+        GeneListRolemodel::thinning(self)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,153 @@
+use crate::gene_ontology::components::Component;
+
+/// Running summary of term and gene activity across retained draws.
+///
+/// Activity probabilities are the fraction of draws in which a node was
+/// active. Monte Carlo standard errors use non-overlapping batch means, so
+/// they account for autocorrelation between consecutive draws; until two
+/// batches are complete they fall back to the independent-draws estimate.
+#[derive(Clone, Debug)]
+pub struct PosteriorSummary {
+    ndraws: usize,
+    batch_size: usize,
+    nbatches: usize,
+
+    term_counts: Vec<usize>,
+    gene_counts: Vec<usize>,
+
+    term_batch_counts: Vec<usize>,
+    term_batch_sum: Vec<f64>,
+    term_batch_sumsq: Vec<f64>,
+}
+
+impl PosteriorSummary {
+    pub fn new(nterms: usize, ngenes: usize) -> Self {
+        Self {
+            ndraws: 0,
+            batch_size: 50,
+            nbatches: 0,
+            term_counts: vec![0; nterms],
+            gene_counts: vec![0; ngenes],
+            term_batch_counts: vec![0; nterms],
+            term_batch_sum: vec![0.0; nterms],
+            term_batch_sumsq: vec![0.0; nterms],
+        }
+    }
+    /// Number of draws per batch used for standard errors. Defaults to 50.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "Batch size must be positive");
+        assert_eq!(self.ndraws, 0, "Batch size must be set before adding draws");
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Combine the summaries of chains run on separate connected components
+    /// into a summary of `nterms` terms and `ngenes` genes. Each summary covers
+    /// the terms and genes of its component, in the order they are listed there.
+    ///
+    /// Panics unless every summary has the same number of draws and batch size.
+    pub fn from_components<'a>(
+        nterms: usize,
+        ngenes: usize,
+        components: impl IntoIterator<Item = (&'a Component, &'a PosteriorSummary)>,
+    ) -> Self {
+        let mut merged: Option<Self> = None;
+        for (component, summary) in components {
+            assert_eq!(summary.nterms(), component.terms.len(), "Summary must cover every term of its component");
+            assert_eq!(summary.ngenes(), component.genes.len(), "Summary must cover every gene of its component");
+            let merged = merged.get_or_insert_with(|| Self {
+                ndraws: summary.ndraws,
+                batch_size: summary.batch_size,
+                nbatches: summary.nbatches,
+                ..Self::new(nterms, ngenes)
+            });
+            assert!(
+                summary.ndraws == merged.ndraws && summary.batch_size == merged.batch_size,
+                "Component summaries must have the same number of draws and batch size",
+            );
+
+            component.terms.iter().enumerate().for_each(|(local_idx, &idx)| {
+                merged.term_counts[idx] = summary.term_counts[local_idx];
+                merged.term_batch_counts[idx] = summary.term_batch_counts[local_idx];
+                merged.term_batch_sum[idx] = summary.term_batch_sum[local_idx];
+                merged.term_batch_sumsq[idx] = summary.term_batch_sumsq[local_idx];
+            });
+            component.genes.iter().enumerate().for_each(|(local_idx, &idx)| {
+                merged.gene_counts[idx] = summary.gene_counts[local_idx];
+            });
+        }
+        merged.unwrap_or_else(|| Self::new(nterms, ngenes))
+    }
+
+    /// Record one draw, given the activity of every term and gene in index order.
+    pub fn add_draw(
+        &mut self,
+        term_activity: impl IntoIterator<Item = bool>,
+        gene_activity: impl IntoIterator<Item = bool>,
+    ) {
+        term_activity.into_iter()
+            .zip(self.term_counts.iter_mut().zip(self.term_batch_counts.iter_mut()))
+            .filter(|(active, _)| *active)
+            .for_each(|(_, (count, batch_count))| {
+                *count += 1;
+                *batch_count += 1;
+            });
+        gene_activity.into_iter()
+            .zip(self.gene_counts.iter_mut())
+            .filter(|(active, _)| *active)
+            .for_each(|(_, count)| *count += 1);
+
+        self.ndraws += 1;
+        if self.ndraws.is_multiple_of(self.batch_size) {
+            let batch_size = self.batch_size as f64;
+            self.term_batch_counts.iter_mut()
+                .zip(self.term_batch_sum.iter_mut().zip(self.term_batch_sumsq.iter_mut()))
+                .for_each(|(batch_count, (sum, sumsq))| {
+                    let mean = *batch_count as f64 / batch_size;
+                    *sum += mean;
+                    *sumsq += mean * mean;
+                    *batch_count = 0;
+                });
+            self.nbatches += 1;
+        }
+    }
+
+    pub fn ndraws(&self) -> usize {
+        self.ndraws
+    }
+    pub fn nterms(&self) -> usize {
+        self.term_counts.len()
+    }
+    pub fn ngenes(&self) -> usize {
+        self.gene_counts.len()
+    }
+
+    /// Posterior probability that term `idx` is active.
+    pub fn term_activity(&self, idx: usize) -> f64 {
+        self.probability(self.term_counts[idx])
+    }
+    /// Posterior probability that gene `idx` is active.
+    pub fn gene_activity(&self, idx: usize) -> f64 {
+        self.probability(self.gene_counts[idx])
+    }
+
+    /// Monte Carlo standard error of [`PosteriorSummary::term_activity`].
+    pub fn term_mcse(&self, idx: usize) -> f64 {
+        if self.nbatches < 2 {
+            let p = self.term_activity(idx);
+            return if self.ndraws == 0 { 0.0 } else { (p * (1.0 - p) / self.ndraws as f64).sqrt() }
+        }
+        let nbatches = self.nbatches as f64;
+        let mean = self.term_batch_sum[idx] / nbatches;
+        let variance = ((self.term_batch_sumsq[idx] - nbatches * mean * mean) / (nbatches - 1.0)).max(0.0);
+        (variance / nbatches).sqrt()
+    }
+
+    fn probability(&self, count: usize) -> f64 {
+        if self.ndraws == 0 {
+            0.0
+        } else {
+            count as f64 / self.ndraws as f64
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use rv::data::Booleable;
 
 use crate::Activeable;
+use serde::{Deserialize, Serialize};
 
 pub trait Saveable {
     type Output;
@@ -11,7 +12,7 @@ pub trait Saveable {
     fn saved(&self) -> Self::Output;
 }
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct SaveableData<T>
 where T: Copy {
     current: T,
@@ -25,6 +26,10 @@ where T: Copy {
             saved,
         }
     }
+    /// Replace the current value, leaving the saved value untouched.
+    pub fn set(&mut self, current: T) {
+        self.current = current;
+    }
 }
 impl<T> Saveable for SaveableData<T> 
 where T: Copy {
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, BufWriter, Write},
+    path::Path,
+};
+
+/// One retained draw of a Rolemodel chain.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Draw {
+    /// Sampler step the draw was taken at, counting burn-in steps.
+    pub iteration: usize,
+    pub log_posterior: f64,
+    pub hyperparameters: BTreeMap<String, f64>,
+    /// Indices of the active wholes, in increasing order.
+    pub active_terms: Vec<usize>,
+}
+
+/// Writes draws to a trace as they are generated, one JSON object per line.
+///
+/// Each line is flushed as soon as it is written, so a trace can be followed
+/// with `tail -f` during a run and read back with [`read_trace`].
+pub struct TraceWriter<W: Write> {
+    writer: W,
+}
+
+impl TraceWriter<BufWriter<std::fs::File>> {
+    pub fn create(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Ok(Self::new(BufWriter::new(std::fs::File::create(path)?)))
+    }
+}
+
+impl<W: Write> TraceWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub fn write_draw(&mut self, draw: &Draw) -> std::io::Result<()> {
+        serde_json::to_writer(&mut self.writer, draw)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Read back the draws of a trace written by [`TraceWriter`], skipping blank lines.
+pub fn read_trace(reader: impl BufRead) -> impl Iterator<Item = std::io::Result<Draw>> {
+    reader.lines()
+        .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+}
@@ -0,0 +1,140 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use crate::common;
+    use rolemodel_gsea::{GeneOntology, GeneSet, Namespace, Node, Part, Whole};
+    use serde::Serialize;
+    use std::io::ErrorKind;
+
+    fn gene_ontology() -> GeneOntology<f64, f64> {
+        let mut gene_ontology = common::gene_ontology(vec![0.001, 0.2, 0.9, 0.5], vec![0.0, 1.0, 0.0]);
+        gene_ontology.term_names_mut().set_description(1, "angiogenesis");
+        gene_ontology.set_term_namespace(0, Namespace::BiologicalProcess);
+        gene_ontology
+    }
+
+    fn binary(gene_ontology: &GeneOntology<f64, f64>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        gene_ontology.write_binary(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let gene_ontology = gene_ontology();
+        let bytes = binary(&gene_ontology);
+        assert_eq!(&bytes[0..4], b"RMGO");
+
+        let loaded = GeneOntology::<f64, f64>::read_binary(bytes.as_slice()).unwrap();
+        assert_eq!(loaded.gene_names().iter().collect::<Vec<_>>(), vec!["IFRD1", "VEGFA", "ACTB", "MYC"]);
+        assert_eq!(loaded.term_names().iter().collect::<Vec<_>>(), vec!["GO:0001", "GO:0002", "GO:0003"]);
+        assert_eq!(loaded.term_names().description(1), Some("angiogenesis"));
+        assert_eq!(loaded.term_names().description(0), None);
+        assert_eq!(loaded.term_namespace(0), Some(Namespace::BiologicalProcess));
+        assert_eq!(loaded.term_namespace(1), None);
+
+        let gene_data: Vec<f64> = loaded.iter_parts().map(|gene| *gene.data()).collect();
+        assert_eq!(gene_data, vec![0.001, 0.2, 0.9, 0.5]);
+        assert_eq!(*loaded.term("GO:0002").unwrap().data(), 1.0);
+        let sizes: Vec<usize> = loaded.iter_wholes().map(|term| term.parts().count()).collect();
+        assert_eq!(sizes, vec![2, 2, 2]);
+        assert_eq!(loaded.gene("VEGFA").unwrap().wholes().count(), 2);
+
+        assert_eq!(binary(&loaded), bytes);
+    }
+
+    #[test]
+    fn binary_rejects_corruption() {
+        let bytes = binary(&gene_ontology());
+
+        let mut corrupted = bytes.clone();
+        *corrupted.last_mut().unwrap() ^= 0xff;
+        let err = GeneOntology::<f64, f64>::read_binary(corrupted.as_slice()).unwrap_err();
+        assert!(err.to_string().contains("checksum"));
+
+        let mut newer = bytes.clone();
+        newer[4] += 1;
+        let err = GeneOntology::<f64, f64>::read_binary(newer.as_slice()).unwrap_err();
+        assert!(err.to_string().contains("format version 5"));
+
+        let truncated = &bytes[..bytes.len() - 3];
+        assert!(GeneOntology::<f64, f64>::read_binary(truncated).is_err());
+        assert!(GeneOntology::<f64, f64>::read_binary(&b"gene,term\n"[..]).is_err());
+    }
+
+    /// Payload layout of a binary gene ontology, to write files that pass the
+    /// checksum but hold an impossible ontology.
+    #[derive(Clone, Serialize)]
+    struct Payload {
+        gene_names: Vec<&'static str>,
+        gene_descriptions: Vec<Option<&'static str>>,
+        term_names: Vec<&'static str>,
+        term_descriptions: Vec<Option<&'static str>>,
+        term_aliases: Vec<Vec<&'static str>>,
+        term_namespaces: Vec<Option<Namespace>>,
+        gene_data: Vec<f64>,
+        term_data: Vec<f64>,
+        adjacency: Vec<(usize, usize)>,
+        term_relations: Vec<(usize, usize)>,
+    }
+
+    fn payload() -> Payload {
+        Payload {
+            gene_names: vec!["IFRD1", "VEGFA"],
+            gene_descriptions: vec![None, None],
+            term_names: vec!["GO:0001", "GO:0002"],
+            term_descriptions: vec![None, None],
+            term_aliases: vec![vec!["GO:0003"], vec![]],
+            term_namespaces: vec![None, None],
+            gene_data: vec![0.001, 0.2],
+            term_data: vec![0.0, 0.0],
+            adjacency: vec![(0, 0), (1, 1)],
+            term_relations: vec![(1, 0)],
+        }
+    }
+
+    fn read(payload: &Payload) -> std::io::Result<GeneOntology<f64, f64>> {
+        let version = &binary(&gene_ontology())[4..8];
+        let payload = postcard::to_stdvec(payload).unwrap();
+        let mut bytes = b"RMGO".to_vec();
+        bytes.extend_from_slice(version);
+        bytes.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+        bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&payload);
+        GeneOntology::<f64, f64>::read_binary(bytes.as_slice())
+    }
+
+    fn assert_invalid(payload: &Payload, message: &str) {
+        let err = read(payload).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().contains(message), "{}", err);
+    }
+
+    #[test]
+    fn binary_rejects_impossible_ontologies() {
+        let loaded = read(&payload()).unwrap();
+        assert_eq!(loaded.term_index("GO:0003"), Some(0));
+
+        let mut repeated_gene = payload();
+        repeated_gene.gene_names[1] = "IFRD1";
+        assert_invalid(&repeated_gene, "Name IFRD1 given to both node 0 and node 1");
+
+        let mut repeated_term = payload();
+        repeated_term.term_names[1] = "GO:0001";
+        assert_invalid(&repeated_term, "Name GO:0001 given to both node 0 and node 1");
+
+        let mut alias_of_term = payload();
+        alias_of_term.term_aliases[0] = vec!["GO:0002"];
+        assert_invalid(&alias_of_term, "Cannot make GO:0002 an alias of node 0");
+
+        let mut repeated_alias = payload();
+        repeated_alias.term_aliases[1] = vec!["GO:0003"];
+        assert_invalid(&repeated_alias, "Cannot make GO:0003 an alias of node 1");
+
+        let mut cycle = payload();
+        cycle.term_relations.push((0, 1));
+        assert_invalid(&cycle, "Term hierarchy has a cycle through term GO:0001");
+    }
+}
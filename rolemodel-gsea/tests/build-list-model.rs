@@ -3,28 +3,21 @@
 #[cfg(test)]
 mod tests {
 
-    use std::{
-        rc::Rc,
-        cell::RefCell,
-        ops::Deref,
-    };
 
     use rolemodel_gsea::{
-        Activeable, GOGeneListRolemodel, Gene, GeneOntology, GeneSet, Node, Part, Rolemodel, Saveable, SaveableData, Term, Whole
+        Activeable, GOGeneListRolemodel, GeneOntology, GeneIdMap, GeneIdResolution, GeneOntologyLoader, GeneSet, MissingPolicy, TableSource, Universe, Node, Part, Rolemodel, Saveable, SaveableData, Whole
     };
     use rv::data::Booleable;
+    use serde::Deserialize;
 
 
     #[test]
     fn basic_gene_ont() {
-        let mut gene_ontology = GeneOntology::<bool, bool>::new(
-            vec![Rc::new(RefCell::new(Gene::new(true, vec![])))],
-            vec![]);
-        let gene = gene_ontology.genes()[0].clone();
-        gene_ontology.mut_terms().push(Rc::new(RefCell::new(Term::new(false, vec![gene]))));
+        let mut gene_ontology = GeneOntology::<bool, bool>::new(vec![true], vec![false]);
+        gene_ontology.add_annotation(0, 0);
 
         assert_eq!(
-            *gene_ontology.iter_wholes().next().unwrap().borrow().parts().next().unwrap().ref_data().deref(), 
+            *gene_ontology.iter_wholes().next().unwrap().parts().next().unwrap().data(), 
             true
         );
     }
@@ -49,37 +42,264 @@ mod tests {
 
     #[test]
     fn read_gene_ont() {
-        let gene_data_file = Some("./tests/data/atovaquone-rnaseq/results.csv".to_string());
-        let term_data_file = None;
-        let adj_file = "./tests/data/atovaquone-rnaseq/adjacency.csv".to_string();
-
-        let gene_ontology = GeneOntology::<f64, f64>::read_scalar_data(
-            gene_data_file, 
-            Some("gene".into()), Some("padj".into()), 
-            term_data_file, 
-            None, None, 
-            adj_file, 
-            Some("symbol".into()), Some("go_id".into())
-        );
+        let gene_ontology = GeneOntologyLoader::<f64, f64>::new()
+            .gene_data("./tests/data/atovaquone-rnaseq/results.csv")
+            .gene_name_column("gene")
+            .gene_data_column("padj")
+            .adjacency("./tests/data/atovaquone-rnaseq/adjacency.csv")
+            .adjacency_term_column("go_id")
+            .adjacency_gene_column("symbol")
+            .load();
 
         assert_eq!(
-            gene_ontology.terms()[0].ref_data().deref(),
+            gene_ontology.whole(0).unwrap().data(),
             &0.0
         );
     }
 
+    #[test]
+    #[should_panic(expected = "Columns must be given for either both names and data in the gene data")]
+    fn loader_rejects_unpaired_columns() {
+        GeneOntologyLoader::<f64, f64>::new()
+            .gene_data("./tests/data/atovaquone-rnaseq/results.csv")
+            .gene_name_column("gene")
+            .adjacency("./tests/data/atovaquone-rnaseq/adjacency.csv")
+            .validate();
+    }
+
+    #[test]
+    fn load_from_reader_and_rows() {
+        let gene_data: &'static [u8] = b"gene\tpadj\nIFRD1\t0.001\nVEGFA\t0.2\n";
+        let gene_ontology = GeneOntologyLoader::<f64, f64>::new()
+            .gene_data(TableSource::reader(gene_data))
+            .gene_name_column("gene")
+            .gene_data_column("padj")
+            .delimiter(b'\t')
+            .adjacency(TableSource::rows(vec![
+                vec!["go_id", "symbol"],
+                vec!["GO:0001", "IFRD1"],
+                vec!["GO:0001", "VEGFA"],
+                vec!["GO:0002", "VEGFA"],
+            ]))
+            .adjacency_term_column("go_id")
+            .adjacency_gene_column("symbol")
+            .load();
+
+        let result: Vec<usize> = gene_ontology.iter_wholes().map(|whole| whole.parts().count()).collect();
+        assert_eq!(result, vec![2, 1]);
+        assert_eq!(*gene_ontology.part(1).unwrap().data(), 0.2);
+    }
+
+    #[test]
+    fn retain_names() {
+        let gene_ontology = GeneOntologyLoader::<f64, f64>::new()
+            .adjacency(TableSource::rows(vec![
+                vec!["GO:0001", "IFRD1"],
+                vec!["GO:0001", "VEGFA"],
+                vec!["GO:0002", "VEGFA"],
+            ]))
+            .term_descriptions(TableSource::rows(vec![
+                vec!["id", "name"],
+                vec!["GO:0002", "angiogenesis"],
+            ]))
+            .term_description_name_column("id")
+            .term_description_column("name")
+            .load();
+
+        assert_eq!(gene_ontology.gene_names().iter().collect::<Vec<_>>(), vec!["IFRD1", "VEGFA"]);
+        assert_eq!(gene_ontology.term_index("GO:0002"), Some(1));
+        assert_eq!(gene_ontology.term_names().description(1), Some("angiogenesis"));
+        assert_eq!(gene_ontology.term_names().description(0), None);
+        assert_eq!(gene_ontology.gene("VEGFA").unwrap().wholes().count(), 2);
+    }
+
+    #[test]
+    fn missing_gene_data() {
+        let load = |policy| GeneOntologyLoader::<f64, f64>::new()
+            .gene_data(TableSource::rows(vec![
+                vec!["gene", "padj"],
+                vec!["IFRD1", "0.001"],
+                vec!["VEGFA", "NA"],
+            ]))
+            .gene_name_column("gene")
+            .gene_data_column("padj")
+            .adjacency(TableSource::rows(vec![
+                vec!["GO:0001", "IFRD1"],
+                vec!["GO:0001", "VEGFA"],
+            ]))
+            .missing_genes(policy)
+            .non_hit_gene_data(|| 1.0)
+            .load_with_report();
+
+        let (gene_ontology, report) = load(MissingPolicy::Drop);
+        assert_eq!(report.missing_genes, vec!["VEGFA".to_string()]);
+        assert_eq!(gene_ontology.genes().len(), 1);
+        assert_eq!(gene_ontology.whole(0).unwrap().parts().count(), 1);
+
+        let (gene_ontology, report) = load(MissingPolicy::NonHit);
+        assert_eq!(report.missing_genes.len(), 1);
+        assert_eq!(*gene_ontology.part(1).unwrap().data(), 1.0);
+    }
+
+    #[test]
+    fn missing_policy_likelihood() {
+        let data_llikelihood = |policy| {
+            let gene_ontology = GeneOntologyLoader::<TermData, GeneData>::new()
+                .gene_data(TableSource::rows(vec![
+                    vec!["gene", "padj"],
+                    vec!["IFRD1", "0.001"],
+                    vec!["VEGFA", "NA"],
+                ]))
+                .gene_name_column("gene")
+                .gene_data_column("padj")
+                .adjacency(TableSource::rows(vec![
+                    vec!["GO:0001", "IFRD1"],
+                    vec!["GO:0001", "VEGFA"],
+                ]))
+                .gene_transform(|x: f64| GeneData::from_bool(x < 0.01))
+                .term_transform(|_: bool| TermData::default())
+                .missing_genes(policy)
+                .non_hit_gene_data(|| GeneData::from_bool(false))
+                .unobserved_gene_data(GeneData::default)
+                .load();
+            GOGeneListRolemodel::new(gene_ontology, 5, 10, 1, 0.2, 0.1, 0.05, 2.0).data_llikelihood()
+        };
+
+        // An unobserved gene adds nothing to the likelihood, as if it were dropped.
+        let dropped = data_llikelihood(MissingPolicy::Drop);
+        let unobserved = data_llikelihood(MissingPolicy::Unobserved);
+        let non_hit = data_llikelihood(MissingPolicy::NonHit);
+        assert_eq!(unobserved, dropped);
+        assert_ne!(non_hit, unobserved);
+    }
+
+    #[test]
+    #[should_panic(expected = "must not convert into a hit or a non-hit")]
+    fn reject_observed_unobserved_data() {
+        // Integer data converts 0 into a non-hit.
+        GeneOntologyLoader::<u8, u8>::new()
+            .gene_data(TableSource::rows(vec![vec!["VEGFA", "NA"]]))
+            .adjacency(TableSource::rows(vec![vec!["GO:0001", "VEGFA"]]))
+            .missing_genes(MissingPolicy::Unobserved)
+            .unobserved_gene_data(|| 0)
+            .load();
+    }
+
+    #[test]
+    fn map_gene_ids() {
+        let id_map = GeneIdMap::from_table(
+            TableSource::rows(vec![
+                vec!["symbol", "ensembl", "alias"],
+                vec!["VEGFA", "ENSG00000112715", "VEGF"],
+                vec!["IFRD1", "ENSG00000006652", "PC4"],
+                vec!["PC4", "ENSG00000000000", ""],
+            ]),
+            "symbol", &["ensembl", "alias"], b',',
+        );
+        assert_eq!(id_map.resolve("vegf"), GeneIdResolution::Mapped("VEGFA".into()));
+        assert_eq!(
+            id_map.resolve("PC4"),
+            GeneIdResolution::Ambiguous(vec!["IFRD1".into(), "PC4".into()])
+        );
+
+        let (gene_ontology, report) = GeneOntologyLoader::<f64, f64>::new()
+            .gene_data(TableSource::rows(vec![
+                vec!["VEGFA", "0.2"],
+                vec!["IFRD1", "0.001"],
+                vec!["ACTB", "0.9"],
+                vec!["VEGF", "0.3"],
+            ]))
+            .adjacency(TableSource::rows(vec![
+                vec!["GO:0001", "ENSG00000112715"],
+                vec!["GO:0001", "ENSG00000006652"],
+                vec!["GO:0002", "ACTB"],
+            ]))
+            .gene_id_map(id_map)
+            .load_with_report();
+
+        assert_eq!(gene_ontology.genes().len(), 3);
+        assert_eq!(gene_ontology.whole(0).unwrap().parts().count(), 2);
+        assert_eq!(report.unmapped_genes, vec!["ACTB".to_string()]);
+        assert_eq!(report.merged_genes, vec!["VEGFA".to_string()]);
+        assert_eq!(*gene_ontology.gene("VEGFA").unwrap().data(), 0.2);
+    }
+
+    #[test]
+    fn reconcile_universe() {
+        let load = |universe| GeneOntologyLoader::<f64, f64>::new()
+            .gene_data(TableSource::rows(vec![
+                vec!["IFRD1", "0.001"],
+                vec!["ACTB", "0.9"],
+            ]))
+            .adjacency(TableSource::rows(vec![
+                vec!["GO:0001", "IFRD1"],
+                vec!["GO:0001", "VEGFA"],
+                vec!["GO:0002", "VEGFA"],
+            ]))
+            .universe(universe)
+            .load_with_report();
+
+        let (gene_ontology, report) = load(Universe::Intersection);
+        assert_eq!(gene_ontology.genes().len(), 1);
+        assert_eq!(report.dropped_genes, vec!["ACTB".to_string(), "VEGFA".to_string()]);
+        assert_eq!(report.emptied_terms, vec!["GO:0002".to_string()]);
+
+        let (gene_ontology, report) = load(Universe::Data);
+        assert_eq!(gene_ontology.genes().len(), 2);
+        assert_eq!(report.unannotated_genes, vec!["ACTB".to_string()]);
+
+        let (gene_ontology, report) = load(Universe::Annotation);
+        assert_eq!(gene_ontology.genes().len(), 2);
+        assert_eq!(report.defaulted_genes, vec!["VEGFA".to_string()]);
+        assert_eq!(*gene_ontology.part(1).unwrap().data(), 0.0);
+    }
+
+    #[derive(Debug, Default, Deserialize, PartialEq)]
+    struct DeseqResult {
+        #[serde(rename = "baseMean")]
+        base_mean: f64,
+        #[serde(rename = "log2FoldChange")]
+        log2_fold_change: f64,
+        padj: Option<f64>,
+    }
+
+    #[test]
+    fn whole_row_gene_data() {
+        let (gene_ontology, report) = GeneOntologyLoader::<f64, DeseqResult>::new()
+            .gene_data(TableSource::rows(vec![
+                vec!["gene", "baseMean", "log2FoldChange", "padj"],
+                vec!["IFRD1", "1309.78", "1.66", "3.9e-49"],
+                vec!["VEGFA", "1137.49", "2.05", "NA"],
+                vec!["ACTB", "NA", "0.01", "0.9"],
+            ]))
+            .gene_name_column("gene")
+            .whole_row_gene_data()
+            .adjacency(TableSource::rows(vec![
+                vec!["GO:0001", "IFRD1"],
+                vec!["GO:0001", "VEGFA"],
+                vec!["GO:0002", "ACTB"],
+            ]))
+            .load_with_report();
+
+        assert_eq!(report.missing_genes, vec!["ACTB".to_string()]);
+        assert_eq!(
+            *gene_ontology.part(1).unwrap().data(),
+            DeseqResult { base_mean: 1137.49, log2_fold_change: 2.05, padj: None }
+        );
+    }
+
     #[derive(Clone, Copy, Debug, Default)]
     struct GeneData {
-        data: bool,
+        data: Option<bool>,
         activity: SaveableData<bool>,
     }
     impl Booleable for GeneData {
         fn try_into_bool(self) -> Option<bool> {
-            Some(self.data)
+            self.data
         }
         fn from_bool(b: bool) -> Self {
             Self {
-                data: b,
+                data: Some(b),
                 activity: SaveableData::new(b, b)
             }
         }
@@ -153,26 +373,19 @@ mod tests {
 
     #[test]
     fn build_gene_list_model() {
-        let gene_data_file = Some("./tests/data/atovaquone-rnaseq/results.csv".to_string());
-        let term_data_file = None;
-        let adj_file = "./tests/data/atovaquone-rnaseq/adjacency.csv".to_string();
-
-        let gene_ontology = GeneOntology
-            ::<TermData, GeneData>
-            ::read_apply_scalar_data::<f64, _, bool, _>
-        (
-            gene_data_file, 
-            Some("gene".into()), Some("padj".into()), 
-            term_data_file, 
-            None, None, 
-            adj_file, 
-            Some("symbol".into()), Some("go_id".into()),
-            |x: f64| GeneData {
-                data: x > 0.01,
+        let gene_ontology = GeneOntologyLoader::<TermData, GeneData>::new()
+            .gene_data("./tests/data/atovaquone-rnaseq/results.csv")
+            .gene_name_column("gene")
+            .gene_data_column("padj")
+            .adjacency("./tests/data/atovaquone-rnaseq/adjacency.csv")
+            .adjacency_term_column("go_id")
+            .adjacency_gene_column("symbol")
+            .gene_transform(|x: f64| GeneData {
+                data: Some(x > 0.01),
                 activity: SaveableData::new(false, false)
-            }, 
-            |x| TermData { activity: SaveableData::new(false, false) },
-        );
+            })
+            .term_transform(|_: bool| TermData { activity: SaveableData::new(false, false) })
+            .load();
 
         let mut rolemodel = GOGeneListRolemodel::new(
             gene_ontology,
@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod tests {
+
+    use rolemodel_gsea::{
+        ChainRng, Checkpoint, Draw, GeneOntology, GeneSetMut, GOGeneListRolemodel, Node, NodeMut, Rolemodel, Saveable, SaveableData,
+    };
+    use rand::{Rng, SeedableRng};
+
+    fn two_term_model() -> GOGeneListRolemodel<SaveableData<bool>, SaveableData<bool>> {
+        model_with_hit_rate(0.1)
+    }
+    fn model_with_hit_rate(true_active_gene_hit_rate: f64) -> GOGeneListRolemodel<SaveableData<bool>, SaveableData<bool>> {
+        let gene_ontology = GeneOntology::from_incidence(
+            vec![SaveableData::new(true, true), SaveableData::new(false, false), SaveableData::new(true, true)],
+            vec![SaveableData::new(false, false), SaveableData::new(false, false)],
+            vec![(0, 0), (0, 1), (1, 1), (1, 2)],
+        );
+        GOGeneListRolemodel::new(gene_ontology, 10, 20, 2, 0.2, true_active_gene_hit_rate, 0.05, 2.0)
+    }
+
+    fn checkpoint_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("rolemodel-{}-{}.ckpt", name, std::process::id()))
+    }
+
+    #[test]
+    fn chain_rng_continues_after_round_trip() {
+        let mut rng = ChainRng::seed_from_u64(7);
+        (0..10).for_each(|_| { rng.gen::<f64>(); });
+
+        let json = serde_json::to_string(&rng).unwrap();
+        let mut restored: ChainRng = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, rng);
+
+        let expected: Vec<u64> = (0..100).map(|_| rng.gen()).collect();
+        let continued: Vec<u64> = (0..100).map(|_| restored.gen()).collect();
+        assert_eq!(continued, expected);
+        assert_ne!(ChainRng::seed_from_u64(7), ChainRng::seed_from_u64(8));
+    }
+
+    #[test]
+    fn save_and_resume_checkpoint() {
+        let mut rng = ChainRng::seed_from_u64(11);
+        rng.gen::<u64>();
+
+        let mut rolemodel = two_term_model();
+        rolemodel.whole_mut(1).unwrap().data_mut().set(true);
+        let checkpoint = rolemodel.checkpoint(14, &rng);
+
+        let path = checkpoint_path("checkpoint");
+        checkpoint.save(&path).unwrap();
+        let loaded = Checkpoint::<SaveableData<bool>, SaveableData<bool>>::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.iteration, 14);
+        assert_eq!(loaded.rng, rng);
+        assert_eq!(loaded.term_data, checkpoint.term_data);
+        assert_eq!(loaded.gene_data, checkpoint.gene_data);
+        assert_eq!(loaded.hyperparameters, checkpoint.hyperparameters);
+
+        let mut resumed = two_term_model();
+        resumed.resume(&loaded);
+        let term_data: Vec<SaveableData<bool>> = resumed.gene_ontology().terms()
+            .map(|term| *term.data())
+            .collect();
+        assert_eq!(term_data, vec![SaveableData::new(false, false), SaveableData::new(true, false)]);
+    }
+
+    #[test]
+    fn resume_continues_bit_for_bit() {
+        let mut straight = two_term_model();
+        let mut straight_rng = ChainRng::seed_from_u64(23);
+        let mut straight_draws: Vec<Draw> = Vec::new();
+        straight.start_chain();
+        let niterations = straight.niterations();
+        straight.run_iterations(&mut straight_rng, 1..=niterations, |_, draw| straight_draws.push(draw.clone()));
+
+        let mut stopped = two_term_model();
+        let mut rng = ChainRng::seed_from_u64(23);
+        let mut draws: Vec<Draw> = Vec::new();
+        stopped.start_chain();
+        stopped.run_iterations(&mut rng, 1..=17, |_, draw| draws.push(draw.clone()));
+
+        let path = checkpoint_path("resume");
+        stopped.checkpoint(17, &rng).save(&path).unwrap();
+        drop(stopped);
+        let loaded = Checkpoint::<SaveableData<bool>, SaveableData<bool>>::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mut resumed = two_term_model();
+        resumed.resume(&loaded);
+        let mut rng = loaded.rng.clone();
+        resumed.run_iterations(&mut rng, loaded.iteration + 1..=niterations, |_, draw| draws.push(draw.clone()));
+
+        assert_eq!(draws.len(), 20);
+        assert_eq!(draws, straight_draws);
+        assert_eq!(rng, straight_rng);
+        let term_data = |model: &GOGeneListRolemodel<SaveableData<bool>, SaveableData<bool>>| model.gene_ontology()
+            .terms()
+            .map(|term| *term.data())
+            .collect::<Vec<SaveableData<bool>>>();
+        assert_eq!(term_data(&resumed), term_data(&straight));
+    }
+
+    #[test]
+    fn save_non_finite_values() {
+        // Genes here are hits exactly when active, which a hit rate of 0 rules out.
+        let mut rolemodel = model_with_hit_rate(0.0);
+        rolemodel.whole_mut(1).unwrap().data_mut().set(true);
+        rolemodel.start_chain();
+        assert_eq!(Rolemodel::posterior_llikelihood(&rolemodel), f64::NEG_INFINITY);
+
+        let mut checkpoint = rolemodel.checkpoint(0, &ChainRng::seed_from_u64(1));
+        checkpoint.hyperparameters.insert("illegal_set_penalty".to_string(), f64::NAN);
+        let mut bytes = Vec::new();
+        checkpoint.write(&mut bytes).unwrap();
+        let loaded = Checkpoint::<SaveableData<bool>, SaveableData<bool>>::read(bytes.as_slice()).unwrap();
+        assert_eq!(loaded.posterior_llikelihood.current(), f64::NEG_INFINITY);
+        assert_eq!(loaded.posterior_llikelihood.saved(), f64::NEG_INFINITY);
+        assert!(loaded.hyperparameters["illegal_set_penalty"].is_nan());
+
+        let mut corrupted = bytes.clone();
+        *corrupted.last_mut().unwrap() ^= 0xff;
+        let err = Checkpoint::<SaveableData<bool>, SaveableData<bool>>::read(corrupted.as_slice()).unwrap_err();
+        assert!(err.to_string().contains("checksum"));
+    }
+}
@@ -0,0 +1,58 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use crate::common;
+    use rolemodel_gsea::{GeneOntology, GeneSet, Namespace, Node, Part, Whole};
+
+    /// The shared chain of terms, plus GO:0004 repeating GO:0001, GO:0005
+    /// repeating GO:0003 in another namespace, and GO:0006 repeating GO:0003.
+    fn gene_ontology() -> GeneOntology<f64, bool> {
+        let mut gene_ontology = common::gene_ontology(vec![true, false, true, false], vec![0.1, 0.2, 0.3]);
+        gene_ontology.term_names_mut().set_description(0, "first");
+        gene_ontology.term_names_mut().set_description(2, "second");
+        for (name, data, genes) in [("GO:0004", 0.4, [1, 0]), ("GO:0005", 0.5, [2, 3]), ("GO:0006", 0.6, [3, 2])] {
+            let term = gene_ontology.add_term(name, data);
+            genes.into_iter().for_each(|gene| { gene_ontology.add_annotation(term, gene); });
+        }
+        gene_ontology.set_term_namespace(4, Namespace::MolecularFunction);
+        gene_ontology
+    }
+
+    #[test]
+    fn collapse_identical_terms() {
+        let (collapsed, report) = gene_ontology().collapse_identical_terms();
+        assert_eq!(report.total_terms, 6);
+        assert_eq!(report.kept_terms, 4);
+        assert_eq!(report.groups, vec![
+            vec!["GO:0001".to_string(), "GO:0004".to_string()],
+            vec!["GO:0003".to_string(), "GO:0006".to_string()],
+        ]);
+
+        assert_eq!(collapsed.term_names().iter().collect::<Vec<_>>(), vec!["GO:0001", "GO:0002", "GO:0003", "GO:0005"]);
+        assert_eq!(collapsed.term_names().aliases(0), ["GO:0004".to_string()]);
+        assert_eq!(collapsed.term_names().description(2), Some("second"));
+        assert_eq!(collapsed.term_index("GO:0006"), Some(2));
+        assert_eq!(*collapsed.term("GO:0004").unwrap().data(), 0.1);
+        assert_eq!(collapsed.term_namespace(3), Some(Namespace::MolecularFunction));
+
+        assert_eq!(collapsed.n_parts(), 4);
+        let actb_terms: Vec<usize> = collapsed.gene("ACTB").unwrap().wholes().map(|term| term.index()).collect();
+        assert_eq!(actb_terms, vec![1, 2, 3]);
+        assert_eq!(collapsed.whole(0).unwrap().parts().count(), 2);
+    }
+
+    #[test]
+    fn collapse_keeps_earlier_aliases() {
+        let (collapsed, _) = gene_ontology().collapse_identical_terms();
+        let (collapsed, report) = collapsed.collapse_identical_terms();
+        assert!(report.groups.is_empty());
+        assert_eq!(collapsed.term_index("GO:0004"), Some(0));
+
+        let mut bytes = vec![];
+        collapsed.write_binary(&mut bytes).unwrap();
+        let restored = GeneOntology::<f64, bool>::read_binary(bytes.as_slice()).unwrap();
+        assert_eq!(restored.term_names().aliases(2), ["GO:0006".to_string()]);
+    }
+}
@@ -0,0 +1,24 @@
+//! Fixtures shared by the integration tests.
+#![allow(dead_code)]
+
+use rolemodel_gsea::{GeneOntology, NameIndex, SaveableData};
+
+/// IFRD1, VEGFA, ACTB and MYC annotated to a chain of three terms: GO:0001
+/// holds IFRD1 and VEGFA, GO:0002 VEGFA and ACTB, and GO:0003 ACTB and MYC.
+pub fn gene_ontology<Td, Gd>(gene_data: Vec<Gd>, term_data: Vec<Td>) -> GeneOntology<Td, Gd> {
+    GeneOntology::from_named_incidence(
+        NameIndex::new(vec!["IFRD1", "VEGFA", "ACTB", "MYC"].into_iter().map(String::from).collect()),
+        gene_data,
+        NameIndex::new(vec!["GO:0001", "GO:0002", "GO:0003"].into_iter().map(String::from).collect()),
+        term_data,
+        vec![(0, 0), (0, 1), (1, 1), (1, 2), (2, 2), (2, 3)],
+    )
+}
+
+/// [`gene_ontology`] with IFRD1 and ACTB active and every term inactive.
+pub fn saveable_gene_ontology() -> GeneOntology<SaveableData<bool>, SaveableData<bool>> {
+    gene_ontology(
+        vec![true, false, true, false].into_iter().map(|hit| SaveableData::new(hit, hit)).collect(),
+        vec![SaveableData::new(false, false); 3],
+    )
+}
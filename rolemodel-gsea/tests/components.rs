@@ -0,0 +1,162 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use crate::common;
+    use rolemodel_gsea::{
+        Activeable, ChainRng, Component, Draw, GeneOntology, GeneSet, GeneSetMut, GOGeneListRolemodel, Node, Rolemodel, Saveable,
+        SaveableData, Whole,
+    };
+    use rand::SeedableRng;
+    use rv::data::Booleable;
+
+    /// Gene data whose hit is kept apart from its activity, as in real data.
+    #[derive(Clone, Copy, Debug, Default)]
+    struct HitGene {
+        hit: Option<bool>,
+        activity: SaveableData<bool>,
+    }
+    impl Booleable for HitGene {
+        fn try_into_bool(self) -> Option<bool> {
+            self.hit
+        }
+        fn from_bool(b: bool) -> Self {
+            Self {
+                hit: Some(b),
+                activity: SaveableData::new(false, false),
+            }
+        }
+    }
+    impl Saveable for HitGene {
+        type Output = bool;
+
+        fn save(&mut self) {
+            self.activity.save()
+        }
+        fn restore(&mut self) {
+            self.activity.restore()
+        }
+        fn current(&self) -> Self::Output {
+            self.activity.current()
+        }
+        fn saved(&self) -> Self::Output {
+            self.activity.saved()
+        }
+    }
+    impl Activeable for HitGene {
+        fn is_active(&self) -> bool {
+            self.activity.current()
+        }
+        fn set_activity(&mut self, b: bool) {
+            self.activity.set_activity(b)
+        }
+        fn is_legal(&self) -> bool {
+            true
+        }
+    }
+
+    /// The shared chain of terms cut between VEGFA and GO:0002, with TP53 in no
+    /// term and GO:0004 holding no gene.
+    fn split<Td, Gd>(mut gene_ontology: GeneOntology<Td, Gd>, tp53: Gd, go0004: Td) -> GeneOntology<Td, Gd> {
+        gene_ontology.remove_annotation(1, 1);
+        gene_ontology.add_gene("TP53", tp53);
+        gene_ontology.add_term("GO:0004", go0004);
+        gene_ontology
+    }
+
+    fn gene_ontology() -> GeneOntology<SaveableData<bool>, SaveableData<bool>> {
+        split(common::saveable_gene_ontology(), SaveableData::new(true, true), SaveableData::new(false, false))
+    }
+
+    fn model() -> GOGeneListRolemodel<SaveableData<bool>, SaveableData<bool>> {
+        GOGeneListRolemodel::new(gene_ontology(), 20, 60, 2, 0.2, 0.9, 0.05, 2.0)
+    }
+
+    fn hit_model() -> GOGeneListRolemodel<SaveableData<bool>, HitGene> {
+        let hits = vec![Some(true), Some(true), Some(false), None].into_iter()
+            .map(|hit| HitGene { hit, ..Default::default() })
+            .collect();
+        let gene_ontology = common::gene_ontology(hits, vec![SaveableData::new(false, false); 3]);
+        let gene_ontology = split(gene_ontology, HitGene::from_bool(true), SaveableData::new(false, false));
+        GOGeneListRolemodel::new(gene_ontology, 20, 60, 2, 0.2, 0.9, 0.05, 2.0)
+    }
+
+    #[test]
+    fn find_connected_components() {
+        let gene_ontology = gene_ontology();
+        assert_eq!(gene_ontology.connected_components(), vec![
+            Component { genes: vec![0, 1], terms: vec![0] },
+            Component { genes: vec![2, 3], terms: vec![1, 2] },
+            Component { genes: vec![], terms: vec![3] },
+            Component { genes: vec![4], terms: vec![] },
+        ]);
+
+        let component = gene_ontology.component_ontology(&gene_ontology.connected_components()[1]);
+        assert_eq!(component.gene_names().iter().collect::<Vec<_>>(), vec!["ACTB", "MYC"]);
+        assert_eq!(component.term_names().iter().collect::<Vec<_>>(), vec!["GO:0002", "GO:0003"]);
+        assert_eq!(component.term("GO:0003").unwrap().parts().map(|gene| gene.index()).collect::<Vec<_>>(), vec![0, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "not in its component")]
+    fn reject_partial_component() {
+        let gene_ontology = gene_ontology();
+        gene_ontology.component_ontology(&Component { genes: vec![0], terms: vec![0] });
+    }
+
+    #[test]
+    fn sample_components() {
+        let mut serial_draws: Vec<Draw> = Vec::new();
+        let mut serial = model();
+        let serial_summary = serial.draw_component_samples_with(&mut ChainRng::seed_from_u64(5), false, |draw| serial_draws.push(draw.clone()));
+
+        let mut parallel_draws: Vec<Draw> = Vec::new();
+        let mut parallel = model();
+        let parallel_summary = parallel.draw_component_samples_with(&mut ChainRng::seed_from_u64(5), true, |draw| parallel_draws.push(draw.clone()));
+
+        assert_eq!(serial_draws, parallel_draws);
+        assert_eq!(serial_draws.len(), 60);
+        assert_eq!(serial_draws[0].iteration, 22);
+        assert!(serial_draws.iter().all(|draw| draw.active_terms.iter().all(|&idx| idx < 4)));
+        assert_eq!(serial_summary.ndraws(), 60);
+        (0..4).for_each(|idx| assert_eq!(serial_summary.term_activity(idx), parallel_summary.term_activity(idx)));
+        assert_eq!(serial_summary.gene_activity(4), 0.0);
+
+        // The merged state is a valid state of the whole model.
+        let log_posterior = Rolemodel::posterior_llikelihood(&serial);
+        assert!((serial.calc_posterior_llikelihood() - log_posterior).abs() < 1e-9);
+        assert!((serial_draws.last().unwrap().log_posterior - log_posterior).abs() < 1e-9);
+        let active: Vec<usize> = serial.iter_wholes()
+            .enumerate()
+            .filter(|(_, term)| term.data().is_active())
+            .map(|(idx, _)| idx)
+            .collect();
+        assert_eq!(active, serial_draws.last().unwrap().active_terms);
+    }
+
+    #[test]
+    fn merged_posterior_matches_single_chain() {
+        let mut draws: Vec<Draw> = Vec::new();
+        let mut merged = hit_model();
+        merged.draw_component_samples_with(&mut ChainRng::seed_from_u64(9), false, |draw| draws.push(draw.clone()));
+        assert_eq!(draws.len(), 60);
+        // Hits survive the chains, and activity follows the terms rather than the hits.
+        assert_eq!(merged.iter_parts().map(|gene| gene.data().hit).collect::<Vec<_>>(), vec![Some(true), Some(true), Some(false), None, Some(true)]);
+        assert!(!merged.part(4).unwrap().data().is_active());
+
+        // A single chain over the whole ontology, put in the state of each merged
+        // draw, has the same log posterior as the sum over the components.
+        for draw in &draws {
+            let mut single = hit_model();
+            draw.active_terms.iter().for_each(|&idx| single.whole_mut(idx).unwrap().set_activity(true));
+            single.start_chain();
+            assert!(
+                (Rolemodel::posterior_llikelihood(&single) - draw.log_posterior).abs() < 1e-9,
+                "draw {} has log posterior {} but the single chain has {}",
+                draw.iteration, draw.log_posterior, Rolemodel::posterior_llikelihood(&single),
+            );
+        }
+        assert!((merged.calc_posterior_llikelihood() - draws.last().unwrap().log_posterior).abs() < 1e-9);
+    }
+}
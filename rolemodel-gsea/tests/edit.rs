@@ -0,0 +1,89 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use crate::common;
+    use rolemodel_gsea::{GeneOntology, GeneSet, GeneSetMut, Namespace, Node, NodeMut, Part, Whole};
+
+    fn gene_ontology() -> GeneOntology<f64, f64> {
+        let mut gene_ontology = common::gene_ontology(vec![0.1, 0.2, 0.3, 0.4], vec![1.0, 2.0, 3.0]);
+        gene_ontology.set_term_namespace(1, Namespace::CellularComponent);
+        gene_ontology
+    }
+
+    fn term_genes(gene_ontology: &GeneOntology<f64, f64>, idx: usize) -> Vec<usize> {
+        gene_ontology.whole(idx).unwrap().parts().map(|gene| gene.index()).collect()
+    }
+    fn gene_terms(gene_ontology: &GeneOntology<f64, f64>, idx: usize) -> Vec<usize> {
+        gene_ontology.part(idx).unwrap().wholes().map(|term| term.index()).collect()
+    }
+
+    #[test]
+    fn add_nodes_and_annotations() {
+        let mut gene_ontology = gene_ontology();
+        let gene = gene_ontology.add_gene("TP53", 0.5);
+        let term = gene_ontology.add_term("GO:0004", 4.0);
+        assert_eq!((gene, term), (4, 3));
+        assert_eq!(gene_ontology.gene_index("TP53"), Some(4));
+        assert_eq!(gene_ontology.term_namespace(term), None);
+
+        assert!(gene_ontology.add_annotation(term, gene));
+        assert!(gene_ontology.add_annotation(term, 0));
+        assert!(!gene_ontology.add_annotation(term, gene));
+        assert_eq!(term_genes(&gene_ontology, term), vec![4, 0]);
+        assert_eq!(gene_terms(&gene_ontology, 0), vec![0, 3]);
+        gene_ontology.validate();
+
+        assert!(gene_ontology.remove_annotation(0, 1));
+        assert!(!gene_ontology.remove_annotation(0, 1));
+        assert_eq!(term_genes(&gene_ontology, 0), vec![0]);
+        assert_eq!(gene_terms(&gene_ontology, 1), vec![1]);
+        gene_ontology.validate();
+    }
+
+    #[test]
+    fn remove_nodes() {
+        let mut gene_ontology = gene_ontology();
+        assert_eq!(gene_ontology.remove_gene(0), 0.1);
+        assert_eq!(gene_ontology.gene_names().iter().collect::<Vec<_>>(), vec!["VEGFA", "ACTB", "MYC"]);
+        assert_eq!(gene_ontology.gene_index("ACTB"), Some(1));
+        assert_eq!(term_genes(&gene_ontology, 0), vec![0]);
+        assert_eq!(term_genes(&gene_ontology, 1), vec![0, 1]);
+        assert_eq!(term_genes(&gene_ontology, 2), vec![1, 2]);
+        gene_ontology.validate();
+
+        assert_eq!(gene_ontology.remove_term(0), 1.0);
+        assert_eq!(gene_ontology.n_wholes(), 2);
+        assert_eq!(gene_ontology.term_index("GO:0002"), Some(0));
+        assert_eq!(gene_ontology.term_namespace(0), Some(Namespace::CellularComponent));
+        assert_eq!(*gene_ontology.whole(0).unwrap().data(), 2.0);
+        assert_eq!(gene_terms(&gene_ontology, 0), vec![0]);
+        assert_eq!(gene_terms(&gene_ontology, 1), vec![0, 1]);
+        gene_ontology.validate();
+
+        // A new gene takes the next free index and a name freed by removal.
+        assert_eq!(gene_ontology.add_gene("IFRD1", 0.5), 3);
+        gene_ontology.validate();
+    }
+
+    #[test]
+    #[should_panic(expected = "given to both node 1 and node 4")]
+    fn reject_duplicate_name() {
+        gene_ontology().add_gene("VEGFA", 0.0);
+    }
+
+    #[test]
+    fn change_node_data() {
+        let mut gene_ontology = gene_ontology();
+        *gene_ontology.part_mut(1).unwrap().data_mut() = 0.5;
+        *gene_ontology.whole_mut(0).unwrap().data_mut() += 1.0;
+        assert!(gene_ontology.part_mut(4).is_none());
+        assert!(gene_ontology.whole_mut(3).is_none());
+
+        let gene = gene_ontology.part(1).unwrap();
+        assert_eq!(*gene.data(), 0.5);
+        assert_eq!(gene.wholes().map(|term| *term.data()).collect::<Vec<_>>(), vec![2.0, 2.0]);
+        assert_eq!(gene, gene_ontology.gene("VEGFA").unwrap());
+    }
+}
@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod tests {
+
+    use rolemodel_gsea::{
+        GeneOntology, GeneOntologyLoader, GeneSet, Namespace, Node, Part, TableSource, TermFilter, Whole,
+    };
+
+    fn loader() -> GeneOntologyLoader<f64, f64> {
+        GeneOntologyLoader::<f64, f64>::new()
+            .gene_data(TableSource::rows(vec![
+                vec!["IFRD1", "0.001"],
+                vec!["VEGFA", "0.2"],
+                vec!["ACTB", "0.9"],
+                vec!["MYC", "0.04"],
+            ]))
+            .adjacency(TableSource::rows(vec![
+                vec!["GO:0001", "IFRD1"],
+                vec!["GO:0002", "VEGFA"],
+                vec!["GO:0002", "ACTB"],
+                vec!["GO:0003", "IFRD1"],
+                vec!["GO:0003", "VEGFA"],
+                vec!["GO:0003", "ACTB"],
+                vec!["GO:0003", "MYC"],
+                vec!["GO:0004", "MYC"],
+                vec!["GO:0004", "VEGFA"],
+                vec!["GO:0004", "UNKNOWN"],
+            ]))
+            .term_namespaces(TableSource::rows(vec![
+                vec!["id", "aspect"],
+                vec!["GO:0001", "P"],
+                vec!["GO:0002", "biological_process"],
+                vec!["GO:0003", "BP"],
+                vec!["GO:0004", "cellular_component"],
+            ]))
+            .term_namespace_name_column("id")
+            .term_namespace_column("aspect")
+    }
+
+    #[test]
+    fn parse_namespaces() {
+        assert_eq!("MF".parse::<Namespace>(), Ok(Namespace::MolecularFunction));
+        assert_eq!("Cellular Component".parse::<Namespace>(), Ok(Namespace::CellularComponent));
+        assert!("GO".parse::<Namespace>().is_err());
+        assert_eq!(Namespace::BiologicalProcess.to_string(), "biological_process");
+    }
+
+    #[test]
+    fn filter_by_size() {
+        let gene_ontology = loader().load();
+        assert_eq!(gene_ontology.term_namespace(3), Some(Namespace::CellularComponent));
+
+        let (filtered, report) = gene_ontology.filter_terms(&TermFilter::new().min_genes(2).max_genes(3));
+        assert_eq!(report.total_terms, 4);
+        assert_eq!(report.kept_terms, 2);
+        assert_eq!(report.too_small, vec!["GO:0001".to_string()]);
+        assert_eq!(report.too_large, vec!["GO:0003".to_string()]);
+        assert_eq!(report.unannotated_genes, vec!["IFRD1".to_string()]);
+
+        assert_eq!(filtered.term_names().iter().collect::<Vec<_>>(), vec!["GO:0002", "GO:0004"]);
+        assert_eq!(filtered.term_namespace(1), Some(Namespace::CellularComponent));
+        assert_eq!(filtered.n_parts(), 4);
+        assert_eq!(filtered.gene("IFRD1").unwrap().wholes().count(), 0);
+        let vegfa_terms: Vec<usize> = filtered.gene("VEGFA").unwrap().wholes().map(|term| term.index()).collect();
+        assert_eq!(vegfa_terms, vec![0, 1]);
+        assert_eq!(*filtered.gene("ACTB").unwrap().data(), 0.9);
+    }
+
+    #[test]
+    fn filter_while_loading() {
+        // Sizes are counted after UNKNOWN, which has no data, leaves the universe.
+        let (gene_ontology, report) = loader()
+            .term_filter(TermFilter::new().namespace(Namespace::BiologicalProcess).namespace(Namespace::CellularComponent).max_genes(2))
+            .load_with_report();
+        let filter_report = report.term_filter.unwrap();
+        assert_eq!(filter_report.too_large, vec!["GO:0003".to_string()]);
+        assert!(filter_report.other_namespace.is_empty());
+        assert_eq!(gene_ontology.term_names().iter().collect::<Vec<_>>(), vec!["GO:0001", "GO:0002", "GO:0004"]);
+
+        let (gene_ontology, report) = loader()
+            .term_filter(TermFilter::new().namespace(Namespace::CellularComponent))
+            .load_with_report();
+        let filter_report = report.term_filter.unwrap();
+        assert_eq!(filter_report.other_namespace, vec!["GO:0001", "GO:0002", "GO:0003"]);
+        assert_eq!(filter_report.unannotated_genes, vec!["IFRD1", "ACTB"]);
+        assert_eq!(gene_ontology.n_wholes(), 1);
+        assert_eq!(gene_ontology.whole(0).unwrap().parts().count(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "min_genes 5 exceeds max_genes 2")]
+    fn reject_empty_size_range() {
+        GeneOntology::<bool, bool>::new(vec![true], vec![false])
+            .filter_terms(&TermFilter::new().min_genes(5).max_genes(2));
+    }
+}
@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod tests {
+
+    use rolemodel_gsea::{
+        Activeable, ChainRng, GeneOntGraph, GeneOntology, GeneSet, GeneSetMut, GOGeneListRolemodel, NameIndex, Node, Part,
+        Rolemodel, SaveableData, Whole,
+    };
+    use rand::SeedableRng;
+
+    fn graph() -> GeneOntGraph<bool, f64> {
+        GeneOntGraph::from_named_incidence(
+            NameIndex::new(vec!["TP53".to_string(), "VEGFA".to_string(), "MYC".to_string()]),
+            vec![0.01, 0.5, 0.02],
+            NameIndex::new(vec!["GO:0001".to_string(), "GO:0002".to_string()]),
+            vec![false, true],
+            vec![(0, 2), (0, 0), (0, 2), (1, 1), (1, 2)],
+        )
+    }
+
+    #[test]
+    fn repeated_annotations_are_kept_once() {
+        let mut graph = graph();
+        assert_eq!(graph.term(0).unwrap().neighbors(), &[0, 2]);
+        assert_eq!(graph.gene(2).unwrap().neighbors(), &[0, 1]);
+
+        assert!(!graph.annotate(1, 1));
+        assert!(graph.annotate(1, 0));
+        assert_eq!(graph.term(1).unwrap().neighbors(), &[0, 1, 2]);
+        assert_eq!(graph.gene(0).unwrap().neighbors(), &[0, 1]);
+    }
+
+    #[test]
+    fn mutate_neighbors() {
+        let mut graph = graph();
+        graph.iter_term_neighbors_mut(0).for_each(|gene| *gene.data_mut() *= 10.0);
+        let gene_data: Vec<f64> = graph.iter_genes().map(|gene| *gene.data()).collect();
+        assert_eq!(gene_data, vec![0.1, 0.5, 0.2]);
+
+        graph.iter_gene_neighbors_mut(2).for_each(|term| *term.data_mut() = !*term.data());
+        let term_data: Vec<bool> = graph.iter_terms().map(|term| *term.data()).collect();
+        assert_eq!(term_data, vec![true, false]);
+        assert_eq!(graph.iter_term_neighbors(1).count(), 2);
+    }
+
+    #[test]
+    fn graph_as_gene_set() {
+        let graph = graph();
+        let gene_set = &graph;
+        assert_eq!(gene_set.n_parts(), 3);
+        assert_eq!(gene_set.n_wholes(), 2);
+
+        let sizes: Vec<usize> = gene_set.iter_wholes().map(|term| term.parts().count()).collect();
+        assert_eq!(sizes, vec![2, 2]);
+
+        let myc = gene_set.part(2).unwrap();
+        assert_eq!(*myc.data(), 0.02);
+        let active: Vec<usize> = myc.wholes()
+            .filter(|term| *term.data())
+            .map(|term| term.index())
+            .collect();
+        assert_eq!(active, vec![1]);
+        assert!(gene_set.whole(2).is_none());
+    }
+
+    #[test]
+    fn convert_to_and_from_gene_ontology() {
+        let gene_ontology: GeneOntology<bool, f64> = graph().into();
+        assert_eq!(gene_ontology.n_wholes(), 2);
+        assert_eq!(gene_ontology.term("GO:0001").unwrap().parts().count(), 2);
+        assert_eq!(*gene_ontology.gene("VEGFA").unwrap().data(), 0.5);
+
+        let graph = GeneOntGraph::from(&gene_ontology);
+        assert_eq!(graph.gene_names().get("MYC"), Some(2));
+        assert_eq!(graph.term(1).unwrap().neighbors(), &[1, 2]);
+        assert_eq!(graph.gene(2).unwrap().neighbors(), &[0, 1]);
+        assert!(*graph.term(1).unwrap().data());
+    }
+
+    #[test]
+    fn sample_graph() {
+        let graph = GeneOntGraph::from_incidence(
+            vec![SaveableData::new(true, true), SaveableData::new(false, false), SaveableData::new(true, true)],
+            vec![SaveableData::new(false, false), SaveableData::new(false, false)],
+            vec![(0, 0), (0, 1), (1, 1), (1, 2)],
+        );
+        let gene_ontology: GeneOntology<_, _> = graph.clone().into();
+
+        let mut graph_model = GOGeneListRolemodel::new(graph, 10, 20, 2, 0.2, 0.9, 0.05, 2.0);
+        let mut graph_draws = Vec::new();
+        graph_model.draw_samples_with(&mut ChainRng::seed_from_u64(3), |_, draw| graph_draws.push(draw.clone()));
+
+        let mut ontology_model = GOGeneListRolemodel::new(gene_ontology, 10, 20, 2, 0.2, 0.9, 0.05, 2.0);
+        let mut ontology_draws = Vec::new();
+        ontology_model.draw_samples_with(&mut ChainRng::seed_from_u64(3), |_, draw| ontology_draws.push(draw.clone()));
+
+        assert_eq!(graph_draws.len(), 20);
+        assert_eq!(graph_draws, ontology_draws);
+
+        // The sampler changed the graph's own data, and parts follow their wholes.
+        let graph = graph_model.gene_ontology();
+        graph.iter_parts().for_each(|gene| {
+            assert_eq!(gene.data().is_active(), gene.wholes().any(|term| term.data().is_active()));
+        });
+        let active_terms: Vec<usize> = graph.iter_wholes()
+            .filter(|term| term.data().is_active())
+            .map(|term| term.index())
+            .collect();
+        assert_eq!(active_terms, graph_draws.last().unwrap().active_terms);
+
+        graph_model.whole_mut(0).unwrap().set_activity(true);
+        assert!(graph_model.gene_ontology().term(0).unwrap().data().is_active());
+    }
+}
@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod tests {
+
+    use rolemodel_gsea::{
+        ActiveSubgraph, EnrichmentMap, GeneOntology, GeneOntologyLoader, GraphFormat, PosteriorSummary, Similarity,
+        TableSource,
+    };
+
+    fn subgraph() -> ActiveSubgraph {
+        let gene_ontology = GeneOntologyLoader::<bool, bool>::new()
+            .gene_data(TableSource::rows(vec![
+                vec!["IFRD1", "true"],
+                vec!["VEGFA", "false"],
+                vec!["ACTB", "true"],
+            ]))
+            .adjacency(TableSource::rows(vec![
+                vec!["GO:0001", "IFRD1"],
+                vec!["GO:0001", "VEGFA"],
+                vec!["GO:0002", "VEGFA"],
+                vec!["GO:0002", "ACTB"],
+                vec!["GO:0003", "ACTB"],
+            ]))
+            .term_descriptions(TableSource::rows(vec![
+                vec!["id", "name"],
+                vec!["GO:0003", "\"quoted\" & <odd>"],
+            ]))
+            .term_description_name_column("id")
+            .term_description_column("name")
+            .load();
+
+        let mut posterior = PosteriorSummary::new(3, 3);
+        posterior.add_draw([true, false, true], [true, true, true]);
+        posterior.add_draw([true, false, false], [true, true, false]);
+        ActiveSubgraph::new(&gene_ontology, &posterior, 0.5)
+    }
+
+    #[test]
+    fn select_active_subgraph() {
+        let subgraph = subgraph();
+        let terms: Vec<&str> = subgraph.terms.iter().map(|term| term.id.as_str()).collect();
+        assert_eq!(terms, vec!["GO:0001", "GO:0003"]);
+        let genes: Vec<&str> = subgraph.genes.iter().map(|gene| gene.symbol.as_str()).collect();
+        assert_eq!(genes, vec!["IFRD1", "VEGFA", "ACTB"]);
+        assert_eq!(subgraph.edges, vec![(0, 0), (0, 1), (1, 2)]);
+        assert_eq!(subgraph.terms[0].observed_hits, 1);
+        assert_eq!(subgraph.terms[1].posterior_activity, 0.5);
+        assert_eq!(subgraph.genes[1].observed, Some(false));
+    }
+
+    #[test]
+    fn write_dot_and_graphml() {
+        let subgraph = subgraph();
+
+        let mut dot = Vec::new();
+        subgraph.write(&mut dot, GraphFormat::Dot).unwrap();
+        let dot = String::from_utf8(dot).unwrap();
+        assert!(dot.starts_with("graph active_terms {\n"));
+        assert!(dot.contains("t1 [shape=box, kind=\"term\", label=\"GO:0003\", id=\"GO:0003\", name=\"\\\"quoted\\\" & <odd>\", size=1, observed_hits=1, posterior=0.5];"));
+        assert!(dot.contains("g0 [shape=ellipse, kind=\"gene\", label=\"IFRD1\", id=\"IFRD1\", observed=\"hit\", posterior=1, fillcolor=\"#f4a582\"];"));
+        assert!(dot.contains("  t1 -- g2;\n"));
+        assert!(dot.ends_with("}\n"));
+
+        let mut graphml = Vec::new();
+        subgraph.write(&mut graphml, GraphFormat::GraphMl).unwrap();
+        let graphml = String::from_utf8(graphml).unwrap();
+        assert!(graphml.contains("<key id=\"posterior\" for=\"node\" attr.name=\"posterior\" attr.type=\"double\"/>"));
+        assert!(graphml.contains("<data key=\"name\">&quot;quoted&quot; &amp; &lt;odd&gt;</data>"));
+        assert!(graphml.contains("<data key=\"observed\">non-hit</data>"));
+        assert_eq!(graphml.matches("<edge ").count(), 3);
+        assert_eq!(GraphFormat::from_path("active.graphml"), Some(GraphFormat::GraphMl));
+        assert_eq!(GraphFormat::from_path("active.gv"), Some(GraphFormat::Dot));
+    }
+
+    #[test]
+    fn enrichment_map() {
+        let gene_ontology = GeneOntologyLoader::<bool, bool>::new()
+            .adjacency(TableSource::rows(vec![
+                vec!["GO:0001", "A"], vec!["GO:0001", "B"], vec!["GO:0001", "C"], vec!["GO:0001", "D"],
+                vec!["GO:0002", "C"], vec!["GO:0002", "D"],
+                vec!["GO:0003", "D"], vec!["GO:0003", "E"], vec!["GO:0003", "F"],
+                vec!["GO:0004", "E"], vec!["GO:0004", "F"],
+            ]))
+            .load();
+        let mut posterior = PosteriorSummary::new(4, 6);
+        posterior.add_draw([true, true, true, false], [true; 6]);
+
+        let jaccard = EnrichmentMap::new(&gene_ontology, &posterior, 0.5, Similarity::Jaccard, 0.25);
+        let terms: Vec<&str> = jaccard.terms.iter().map(|term| term.id.as_str()).collect();
+        assert_eq!(terms, vec!["GO:0001", "GO:0002", "GO:0003"]);
+        assert_eq!(jaccard.edges, vec![(0, 1, 0.5), (1, 2, 0.25)]);
+
+        let overlap = EnrichmentMap::new(&gene_ontology, &posterior, 0.5, Similarity::Overlap, 0.25);
+        assert_eq!(overlap.edges, vec![(0, 1, 1.0), (0, 2, 1.0 / 3.0), (1, 2, 0.5)]);
+
+        let mut graphml = Vec::new();
+        overlap.write(&mut graphml, GraphFormat::GraphMl).unwrap();
+        let graphml = String::from_utf8(graphml).unwrap();
+        assert!(graphml.contains("<key id=\"overlap\" for=\"edge\" attr.name=\"overlap\" attr.type=\"double\"/>"));
+        assert!(graphml.contains("<edge source=\"t1\" target=\"t2\">\n      <data key=\"overlap\">0.5</data>"));
+
+        let mut dot = Vec::new();
+        jaccard.write(&mut dot, GraphFormat::Dot).unwrap();
+        let dot = String::from_utf8(dot).unwrap();
+        assert!(dot.contains("  t0 -- t1 [jaccard=0.5, weight=0.5, penwidth=3.00];\n"));
+    }
+
+    #[test]
+    fn enrichment_map_counts_repeated_annotations_once() {
+        // Gene 0 is annotated to term 0 twice.
+        let gene_ontology = GeneOntology::<bool, bool>::from_incidence(
+            vec![true, false],
+            vec![false, false],
+            vec![(0, 0), (0, 1), (0, 0), (1, 0)],
+        );
+        let mut posterior = PosteriorSummary::new(2, 2);
+        posterior.add_draw([true, true], [true, true]);
+
+        let map = EnrichmentMap::new(&gene_ontology, &posterior, 0.5, Similarity::Jaccard, 0.1);
+        let overlaps = gene_ontology.term_overlaps(Similarity::Jaccard, 0.1);
+        assert_eq!(map.edges, vec![(0, 1, 0.5)]);
+        assert_eq!(overlaps.get(0, 1).unwrap().jaccard, 0.5);
+        assert_eq!(map.terms[0].size, 2);
+    }
+}
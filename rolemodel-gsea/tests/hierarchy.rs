@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod tests {
+
+    use rolemodel_gsea::{GeneOntology, GeneOntologyLoader, HierarchicalWhole, TableSource};
+
+    fn loader() -> GeneOntologyLoader<f64, f64> {
+        GeneOntologyLoader::<f64, f64>::new()
+            .adjacency(TableSource::rows(vec![
+                vec!["GO:0001", "IFRD1"],
+                vec!["GO:0001", "VEGFA"],
+                vec!["GO:0001", "ACTB"],
+                vec!["GO:0001", "MYC"],
+                vec!["GO:0002", "IFRD1"],
+                vec!["GO:0002", "VEGFA"],
+                vec!["GO:0003", "VEGFA"],
+                vec!["GO:0003", "ACTB"],
+                vec!["GO:0004", "VEGFA"],
+                vec!["GO:0005", "IFRD1"],
+                vec!["GO:0005", "VEGFA"],
+            ]))
+            // GO:0009 has no annotations, so GO:0003 reaches GO:0001 through it.
+            .term_hierarchy(TableSource::rows(vec![
+                vec!["child", "parent"],
+                vec!["GO:0004", "GO:0002"],
+                vec!["GO:0004", "GO:0003"],
+                vec!["GO:0002", "GO:0001"],
+                vec!["GO:0003", "GO:0009"],
+                vec!["GO:0009", "GO:0001"],
+                vec!["GO:0005", "GO:0002"],
+                vec!["GO:0004", "GO:0002"],
+            ]))
+            .term_hierarchy_child_column("child")
+            .term_hierarchy_parent_column("parent")
+    }
+
+    fn indices<W: HierarchicalWhole>(wholes: impl Iterator<Item = W>) -> Vec<usize> {
+        wholes.map(|whole| HierarchicalWhole::index(&whole)).collect()
+    }
+
+    #[test]
+    fn load_hierarchy() {
+        let (gene_ontology, report) = loader().load_with_report();
+        assert_eq!(report.bridged_hierarchy_terms, vec!["GO:0009".to_string()]);
+
+        let leaf = gene_ontology.term("GO:0004").unwrap();
+        assert_eq!(indices(leaf.parents()), vec![1, 2]);
+        assert_eq!(indices(leaf.ancestors()), vec![1, 2, 0]);
+        assert_eq!(leaf.depth(), 2);
+        assert_eq!(leaf.children().count(), 0);
+
+        let root = gene_ontology.term("GO:0001").unwrap();
+        assert_eq!(root.depth(), 0);
+        assert_eq!(indices(root.children()), vec![1, 2]);
+        assert_eq!(indices(root.descendants()), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn keep_ancestry_when_terms_are_dropped() {
+        let gene_ontology = loader().load();
+        let subgraph = gene_ontology.induced_by_terms(|term| [0, 3].contains(&term.index()));
+        assert_eq!(indices(subgraph.term("GO:0004").unwrap().parents()), vec![0]);
+
+        let (collapsed, report) = gene_ontology.collapse_identical_terms();
+        assert_eq!(report.groups, vec![vec!["GO:0002".to_string(), "GO:0005".to_string()]]);
+        assert_eq!(indices(collapsed.term("GO:0005").unwrap().parents()), vec![0]);
+        assert_eq!(indices(collapsed.term("GO:0002").unwrap().children()), vec![3]);
+        collapsed.validate();
+
+        let mut bytes = vec![];
+        collapsed.write_binary(&mut bytes).unwrap();
+        let restored = GeneOntology::<f64, f64>::read_binary(bytes.as_slice()).unwrap();
+        assert_eq!(indices(restored.term("GO:0004").unwrap().ancestors()), vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn edit_hierarchy() {
+        let mut gene_ontology = loader().load();
+        gene_ontology.remove_term(1);
+        gene_ontology.validate();
+        // GO:0004 and GO:0005 inherit the parent of GO:0002.
+        assert_eq!(indices(gene_ontology.term("GO:0004").unwrap().parents()), vec![1, 0]);
+        assert_eq!(indices(gene_ontology.term("GO:0005").unwrap().parents()), vec![0]);
+
+        assert!(gene_ontology.remove_term_relation(2, 0));
+        assert!(!gene_ontology.remove_term_relation(2, 0));
+        assert!(gene_ontology.add_term_relation(3, 2));
+        assert!(!gene_ontology.add_term_relation(3, 2));
+        assert_eq!(gene_ontology.term("GO:0005").unwrap().depth(), 3);
+        gene_ontology.validate();
+    }
+
+    #[test]
+    #[should_panic(expected = "Term hierarchy has a cycle")]
+    fn reject_cycle() {
+        let mut gene_ontology = loader().load();
+        gene_ontology.add_term_relation(0, 3);
+        gene_ontology.validate();
+    }
+}
@@ -0,0 +1,65 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use crate::common;
+    use rolemodel_gsea::{
+        Activeable, ChainRng, GeneOntGraph, GeneSet, GeneSetMut, GOGeneListRolemodel, Node, NodeMut, Part, Rolemodel, Saveable, SaveableData,
+    };
+    use rand::SeedableRng;
+
+    /// Written against the traits only, so it runs on any gene set.
+    fn activate_covered_parts<G>(gene_set: &mut G)
+    where
+        G: GeneSetMut,
+        G::PartData: Activeable,
+        G::WholeData: Activeable,
+    {
+        let covered: Vec<bool> = gene_set.iter_parts()
+            .map(|part| part.wholes().any(|whole| whole.data().is_active()))
+            .collect();
+        for (idx, covered) in covered.into_iter().enumerate() {
+            gene_set.part_mut(idx).unwrap().set_activity(covered);
+        }
+    }
+
+    #[test]
+    fn set_activity_through_node() {
+        let mut gene_ontology = common::saveable_gene_ontology();
+        let mut term = gene_ontology.whole_mut(1).unwrap();
+        term.set_activity(true);
+        assert!(term.is_active());
+        assert!(gene_ontology.term("GO:0002").unwrap().data().is_active());
+
+        activate_covered_parts(&mut gene_ontology);
+        let active: Vec<bool> = gene_ontology.iter_parts().map(|gene| gene.data().is_active()).collect();
+        assert_eq!(active, vec![false, true, true, false]);
+
+        *gene_ontology.part_mut(0).unwrap().data_mut() = SaveableData::new(true, false);
+        assert!(!gene_ontology.part(0).unwrap().data().saved());
+    }
+
+    #[test]
+    fn set_activity_through_graph_node() {
+        let mut graph = GeneOntGraph::from(&common::saveable_gene_ontology());
+        graph.term_mut(1).unwrap().set_activity(true);
+        graph.iter_term_neighbors_mut(1).for_each(|gene| gene.set_activity(false));
+
+        let active: Vec<bool> = graph.iter_genes().map(|gene| gene.data().is_active()).collect();
+        assert_eq!(active, vec![true, false, false, false]);
+        assert!(graph.term(1).unwrap().is_active());
+    }
+
+    #[test]
+    fn sample_gene_ontology() {
+        let mut rolemodel = GOGeneListRolemodel::new(common::saveable_gene_ontology(), 10, 20, 2, 0.2, 0.9, 0.05, 2.0);
+        let mut rng = ChainRng::seed_from_u64(3);
+        let mut draws = Vec::new();
+        rolemodel.draw_samples_with(&mut rng, |_, draw| draws.push(draw.clone()));
+
+        assert_eq!(draws.len(), 20);
+        assert!(draws.iter().all(|draw| draw.log_posterior.is_finite()));
+        assert!(draws.iter().all(|draw| draw.active_terms.iter().all(|&idx| idx < 3)));
+    }
+}
@@ -0,0 +1,46 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use crate::common;
+    use rolemodel_gsea::{PosteriorSummary, ResultTables, TableFormat};
+
+    #[test]
+    fn write_result_tables() {
+        let gene_ontology = common::gene_ontology(vec![true, false, true, false], vec![false; 3]);
+
+        let mut posterior = PosteriorSummary::new(3, 4).with_batch_size(2);
+        posterior.add_draw([true, false, false], [true, true, false, false]);
+        posterior.add_draw([true, true, false], [true, true, true, false]);
+        posterior.add_draw([false, false, false], [false, false, false, false]);
+        posterior.add_draw([true, false, false], [true, true, false, false]);
+
+        let tables = ResultTables::new(&gene_ontology, &posterior, 0.5).with_precision(3);
+
+        let mut terms = vec![];
+        tables.write_terms(&mut terms, TableFormat::Csv).unwrap();
+        assert_eq!(
+            String::from_utf8(terms).unwrap(),
+            "id,name,size,observed_hits,posterior_activity,mcse,merged_terms\n\
+             GO:0001,,2,1,0.75,0.25,\n\
+             GO:0002,,2,1,0.25,0.25,\n\
+             GO:0003,,2,1,0.0,0.0,\n"
+        );
+
+        let mut genes = vec![];
+        tables.write_genes(&mut genes, TableFormat::Tsv).unwrap();
+        assert_eq!(
+            String::from_utf8(genes).unwrap(),
+            "symbol\tobserved\tposterior_activity\texplaining_terms\n\
+             IFRD1\ttrue\t0.75\tGO:0001\n\
+             VEGFA\tfalse\t0.75\tGO:0001\n\
+             ACTB\ttrue\t0.25\t\n\
+             MYC\tfalse\t0.0\t\n"
+        );
+
+        let mut json = vec![];
+        tables.write_terms(&mut json, TableFormat::Json).unwrap();
+        assert!(String::from_utf8(json).unwrap().contains("\"posterior_activity\": 0.75"));
+    }
+}
@@ -0,0 +1,73 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use crate::common;
+    use rolemodel_gsea::{GeneOntology, Similarity, TermOverlap};
+
+    /// The shared chain of terms, plus GO:0004 holding VEGFA, ACTB and MYC, and
+    /// GO:0005 holding no gene.
+    fn gene_ontology() -> GeneOntology<f64, f64> {
+        let mut gene_ontology = common::gene_ontology(vec![0.0; 4], vec![0.0; 3]);
+        let wide = gene_ontology.add_term("GO:0004", 0.0);
+        (1..4).for_each(|gene_idx| { gene_ontology.add_annotation(wide, gene_idx); });
+        gene_ontology.add_term("GO:0005", 0.0);
+        gene_ontology
+    }
+
+    #[test]
+    fn overlap_of_two_terms() {
+        let gene_ontology = gene_ontology();
+        assert_eq!(gene_ontology.term_overlap(3, 1), TermOverlap {
+            term_a: 1,
+            term_b: 3,
+            intersection: 2,
+            jaccard: 2.0 / 3.0,
+            overlap_coefficient: 1.0,
+        });
+        let overlap = gene_ontology.term_overlap(0, 3);
+        assert_eq!(overlap.intersection, 1);
+        assert_eq!(overlap.jaccard, 0.25);
+        assert_eq!(overlap.overlap_coefficient, 0.5);
+        assert_eq!(gene_ontology.term_overlap(0, 2).intersection, 0);
+        assert_eq!(gene_ontology.term_overlap(2, 4).overlap_coefficient, 0.0);
+    }
+
+    #[test]
+    fn sparse_overlaps_match_pairwise() {
+        let gene_ontology = gene_ontology();
+        for (similarity, threshold) in [(Similarity::Jaccard, 0.01), (Similarity::Overlap, 0.5), (Similarity::Intersection, 2.0)] {
+            let overlaps = gene_ontology.term_overlaps(similarity, threshold);
+            let expected: Vec<TermOverlap> = (0..5)
+                .flat_map(|a| (a + 1..5).map(move |b| (a, b)))
+                .map(|(a, b)| gene_ontology.term_overlap(a, b))
+                .filter(|overlap| overlap.score(similarity) >= threshold)
+                .collect();
+            assert_eq!(overlaps.iter().copied().collect::<Vec<_>>(), expected);
+            assert_eq!(overlaps.nterms(), 5);
+        }
+
+        let overlaps = gene_ontology.term_overlaps(Similarity::Jaccard, 0.01);
+        assert_eq!(overlaps.len(), 5);
+        assert_eq!(overlaps.get(3, 2).unwrap().overlap_coefficient, 1.0);
+        assert!(overlaps.get(0, 2).is_none());
+        assert!(overlaps.term(4).next().is_none());
+        assert_eq!(overlaps.term(1).map(|overlap| (overlap.term_a, overlap.term_b)).collect::<Vec<_>>(), vec![(0, 1), (1, 2), (1, 3)]);
+    }
+
+    #[test]
+    fn repeated_annotation_counts_once() {
+        // Term 1 lists gene 1 twice.
+        let gene_ontology = GeneOntology::from_incidence(vec![0.0; 2], vec![0.0; 2], vec![(0, 0), (0, 1), (1, 1), (1, 1)]);
+        let overlap = gene_ontology.term_overlap(0, 1);
+        assert_eq!((overlap.intersection, overlap.jaccard, overlap.overlap_coefficient), (1, 0.5, 1.0));
+        assert_eq!(gene_ontology.term_overlaps(Similarity::Intersection, 1.0).get(0, 1), Some(&overlap));
+    }
+
+    #[test]
+    #[should_panic(expected = "threshold must be positive")]
+    fn reject_zero_threshold() {
+        gene_ontology().term_overlaps(Similarity::Jaccard, 0.0);
+    }
+}
@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod tests {
+
+    use rolemodel_gsea::{
+        effective_sample_size, split_rhat, ChainRng, Draw, GeneOntologyLoader, HtmlReport,
+        PosteriorSummary, TableSource,
+    };
+    use rand::{Rng, SeedableRng};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn convergence_diagnostics() {
+        let mut rng = ChainRng::seed_from_u64(3);
+        let mixed: Vec<Vec<f64>> = (0..4).map(|_| (0..500).map(|_| rng.gen::<f64>()).collect()).collect();
+        assert!((split_rhat(&mixed) - 1.0).abs() < 0.02);
+        let ess = effective_sample_size(&mixed);
+        assert!(ess > 1500.0 && ess < 2500.0, "ESS {} of 2000 independent draws", ess);
+
+        let stuck: Vec<Vec<f64>> = (0..4)
+            .map(|chain| (0..500).map(|_| chain as f64 + rng.gen::<f64>()).collect())
+            .collect();
+        assert!(split_rhat(&stuck) > 1.5);
+
+        let sticky: Vec<Vec<f64>> = vec![(0..500).map(|i| (i / 50) as f64).collect()];
+        assert!(effective_sample_size(&sticky) < 50.0);
+
+        assert_eq!(effective_sample_size(&[vec![2.0; 10]]), 10.0);
+        assert_eq!(split_rhat(&[vec![2.0; 10]]), 1.0);
+        assert!(split_rhat(&[vec![1.0, 2.0]]).is_nan());
+    }
+
+    #[test]
+    fn render_html_report() {
+        let gene_ontology = GeneOntologyLoader::<bool, bool>::new()
+            .gene_data(TableSource::rows(vec![
+                vec!["IFRD1", "true"],
+                vec!["VEGFA", "false"],
+                vec!["ACTB", "false"],
+            ]))
+            .adjacency(TableSource::rows(vec![
+                vec!["GO:0001", "IFRD1"],
+                vec!["GO:0001", "VEGFA"],
+                vec!["GO:0002", "VEGFA"],
+                vec!["GO:0002", "ACTB"],
+            ]))
+            .term_descriptions(TableSource::rows(vec![
+                vec!["id", "name"],
+                vec!["GO:0001", "response to <stress> & injury"],
+            ]))
+            .term_description_name_column("id")
+            .term_description_column("name")
+            .load();
+
+        let mut posterior = PosteriorSummary::new(2, 3);
+        let chains: Vec<Vec<Draw>> = (0..2)
+            .map(|chain| (1..=20)
+                .map(|i| {
+                    let active_terms = if (i + chain) % 3 == 0 { vec![0, 1] } else { vec![0] };
+                    posterior.add_draw([true, active_terms.len() == 2], [true, true, active_terms.len() == 2]);
+                    Draw {
+                        iteration: 100 + 2 * i,
+                        log_posterior: -10.0 - active_terms.len() as f64,
+                        hyperparameters: BTreeMap::new(),
+                        active_terms,
+                    }
+                })
+                .collect())
+            .collect();
+
+        let html = HtmlReport::new(&gene_ontology, &posterior, &chains)
+            .title("Atovaquone <test>")
+            .parameter("seed", 3)
+            .top_terms(1)
+            .render();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h1>Atovaquone &lt;test&gt;</h1>"));
+        assert!(html.contains("<tr><th>seed</th><td>3</td></tr>"));
+        assert!(html.contains("<tr><th>chains</th><td>2</td></tr>"));
+        assert!(html.contains("<h2>Top 1 terms</h2>"));
+        assert!(html.contains("response to &lt;stress&gt; &amp; injury"));
+        assert!(!html.contains("<td>GO:0002</td>"));
+        assert!(html.contains("<tr class=\"hit\"><td>IFRD1</td><td>hit</td>"));
+        assert_eq!(html.matches("<svg").count(), 2);
+        assert_eq!(html.matches("<polyline").count(), 4);
+        assert!(!html.contains("<script"));
+    }
+}
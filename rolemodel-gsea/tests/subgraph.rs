@@ -0,0 +1,66 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use crate::common;
+    use rolemodel_gsea::{
+        ChainRng, GeneOntology, GeneSet, GOGeneListRolemodel, Namespace, Node, Part, Rolemodel, Saveable, SaveableData, Whole,
+    };
+    use rand::SeedableRng;
+
+    fn gene_ontology() -> GeneOntology<SaveableData<bool>, SaveableData<bool>> {
+        let mut gene_ontology = common::saveable_gene_ontology();
+        gene_ontology.term_names_mut().set_description(1, "immune response");
+        gene_ontology.set_term_namespace(1, Namespace::BiologicalProcess);
+        gene_ontology
+    }
+
+    #[test]
+    fn induced_by_terms() {
+        let gene_ontology = gene_ontology();
+        let immune = gene_ontology.induced_by_terms(|term| {
+            gene_ontology.term_names().description(term.index()).is_some_and(|name| name.contains("immune"))
+        });
+
+        assert_eq!(immune.term_names().iter().collect::<Vec<_>>(), vec!["GO:0002"]);
+        assert_eq!(immune.term_names().description(0), Some("immune response"));
+        assert_eq!(immune.term_namespace(0), Some(Namespace::BiologicalProcess));
+        assert_eq!(immune.gene_names().iter().collect::<Vec<_>>(), vec!["VEGFA", "ACTB"]);
+        assert!(immune.gene("ACTB").unwrap().data().current());
+        assert_eq!(immune.whole(0).unwrap().parts().map(|gene| gene.index()).collect::<Vec<_>>(), vec![0, 1]);
+    }
+
+    #[test]
+    fn induced_by_genes() {
+        let gene_ontology = gene_ontology();
+        let panel = ["IFRD1", "ACTB"];
+        let subgraph = gene_ontology.induced_by_genes(|gene| {
+            panel.contains(&gene_ontology.gene_names().name(gene.index()).unwrap())
+        });
+
+        assert_eq!(subgraph.gene_names().iter().collect::<Vec<_>>(), vec!["IFRD1", "ACTB"]);
+        assert_eq!(subgraph.term_names().iter().collect::<Vec<_>>(), vec!["GO:0001", "GO:0002", "GO:0003"]);
+        let sizes: Vec<usize> = subgraph.iter_wholes().map(|term| term.parts().count()).collect();
+        assert_eq!(sizes, vec![1, 1, 1]);
+        assert_eq!(subgraph.gene("ACTB").unwrap().wholes().map(|term| term.index()).collect::<Vec<_>>(), vec![1, 2]);
+
+        // The original ontology is untouched.
+        assert_eq!(gene_ontology.n_parts(), 4);
+        assert_eq!(gene_ontology.whole(2).unwrap().parts().count(), 2);
+    }
+
+    #[test]
+    fn sample_subgraph() {
+        let subgraph = gene_ontology().induced_subgraph(&[3, 2], &[2]);
+        assert_eq!(subgraph.gene_names().iter().collect::<Vec<_>>(), vec!["MYC", "ACTB"]);
+
+        let mut rolemodel = GOGeneListRolemodel::new(subgraph, 10, 20, 1, 0.2, 0.9, 0.05, 2.0);
+        let mut draws = 0;
+        rolemodel.draw_samples_with(&mut ChainRng::seed_from_u64(1), |_, draw| {
+            assert!(draw.active_terms.iter().all(|&idx| idx == 0));
+            draws += 1;
+        });
+        assert_eq!(draws, 20);
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+
+    use rolemodel_gsea::{read_trace, CmdStanHeader, CmdStanWriter, Draw, TraceWriter};
+    use std::collections::BTreeMap;
+
+    fn draw(iteration: usize, active_terms: Vec<usize>) -> Draw {
+        Draw {
+            iteration,
+            log_posterior: -12.5 - iteration as f64,
+            hyperparameters: BTreeMap::from([
+                ("set_activity_probability".to_string(), 0.2),
+                ("true_active_gene_hit_rate".to_string(), 0.9),
+            ]),
+            active_terms,
+        }
+    }
+
+    #[test]
+    fn write_and_read_trace() {
+        let draws = vec![draw(10, vec![]), draw(12, vec![0, 3]), draw(14, vec![3])];
+
+        let mut writer = TraceWriter::new(Vec::new());
+        draws.iter().for_each(|draw| writer.write_draw(draw).unwrap());
+        let trace = writer.into_inner();
+
+        let text = String::from_utf8(trace.clone()).unwrap();
+        assert_eq!(text.lines().count(), 3);
+        assert!(text.lines().nth(1).unwrap().contains("\"active_terms\":[0,3]"));
+
+        let read: Vec<Draw> = read_trace(trace.as_slice())
+            .collect::<std::io::Result<Vec<Draw>>>()
+            .unwrap();
+        assert_eq!(read, draws);
+    }
+
+    #[test]
+    fn read_partial_trace() {
+        let mut writer = TraceWriter::new(Vec::new());
+        writer.write_draw(&draw(1, vec![2])).unwrap();
+        let mut trace = writer.into_inner();
+        trace.extend_from_slice(b"\n{\"iteration\":2,\"log_pos");
+
+        let mut read = read_trace(trace.as_slice());
+        assert_eq!(read.next().unwrap().unwrap(), draw(1, vec![2]));
+        assert!(read.next().unwrap().is_err());
+        assert!(read.next().is_none());
+    }
+
+    #[test]
+    fn write_cmdstan_csv() {
+        let header = CmdStanHeader::new(2, 4)
+            .sampler(100, 3, 2)
+            .seed(42)
+            .hyperparameters(["set_activity_probability", "true_active_gene_hit_rate"])
+            .term_names(["GO:0001", "GO:0002", "GO:0003", "GO:0004"]);
+        assert_eq!(
+            header.chain_path("out/atovaquone"),
+            std::path::PathBuf::from("out/atovaquone_2.csv"),
+        );
+
+        let mut writer = CmdStanWriter::new(Vec::new(), header).unwrap();
+        [draw(102, vec![]), draw(104, vec![0, 3]), draw(106, vec![3])].iter()
+            .for_each(|draw| writer.write_draw(draw).unwrap());
+        let text = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+        assert!(text.contains("#     num_warmup = 100\n"));
+        assert!(text.contains("#     thin = 2\n"));
+        assert!(text.contains("# id = 2\n"));
+        assert!(text.contains("#   seed = 42\n"));
+        assert!(text.contains("# term.4: GO:0004\n"));
+        let fields: Vec<&str> = text.lines()
+            .filter_map(|line| line.strip_prefix('#')?.split_once(" = "))
+            .map(|(key, _)| key.trim())
+            .collect();
+        assert_eq!(fields, vec![
+            "model", "method", "num_samples", "num_warmup", "save_warmup", "thin", "engaged", "id", "seed",
+        ]);
+        let rows: Vec<&str> = text.lines().filter(|line| !line.starts_with('#')).collect();
+        assert_eq!(rows, vec![
+            "lp__,set_activity_probability,true_active_gene_hit_rate,term.1,term.2,term.3,term.4",
+            "-114.5,0.2,0.9,0,0,0,0",
+            "-116.5,0.2,0.9,1,0,0,1",
+            "-118.5,0.2,0.9,0,0,0,1",
+        ]);
+
+        // Read as CSV readers of CmdStan output do: comment lines skipped, a
+        // header row, then one row of numbers per draw.
+        let mut reader = csv::ReaderBuilder::new()
+            .comment(Some(b'#'))
+            .from_reader(text.as_bytes());
+        assert_eq!(reader.headers().unwrap().get(0), Some("lp__"));
+        let draws: Vec<Vec<f64>> = reader.deserialize().map(|row| row.unwrap()).collect();
+        assert_eq!(draws.len(), 3);
+        assert_eq!(draws[1], vec![-116.5, 0.2, 0.9, 1.0, 0.0, 0.0, 1.0]);
+    }
+}